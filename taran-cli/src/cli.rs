@@ -1,5 +1,7 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
+use std::str::FromStr;
+use taran_config::{HumanDuration, Override};
 
 #[derive(Parser)]
 #[command(name = "taran")]
@@ -19,17 +21,38 @@ pub struct Cli {
 pub enum Commands {
     /// Run a load test scenario
     Run {
-        /// Path to the scenario file (TOML)
+        /// Path to the scenario file (.toml, .yaml/.yml or .json)
         #[arg(value_name = "SCENARIO")]
         scenario: PathBuf,
 
-        /// Override number of virtual users
+        /// Override number of virtual users of every load profile
         #[arg(short = 'u', long)]
         users: Option<usize>,
 
-        /// Override test duration (e.g., "60s", "5m")
-        #[arg(short = 'd', long)]
-        duration: Option<String>,
+        /// Override test duration of every load profile (e.g., "60s", "5m")
+        #[arg(short = 'd', long, value_parser = parse_duration)]
+        duration: Option<HumanDuration>,
+
+        /// Set any scenario field before validation; may be repeated
+        #[arg(
+            long = "set",
+            value_name = "KEY=VALUE",
+            long_help = "Set any scenario field before validation, with a dotted KEY \
+                         (e.g. \"load_profile.users=50\", \"steps.0.url=http://staging/\"); \
+                         may be repeated"
+        )]
+        overrides: Vec<Override>,
+
+        /// Read `${NAME}` variables from this .env file; may be repeated,
+        /// the process environment takes precedence
+        #[arg(long = "env-file", value_name = "PATH")]
+        env_files: Vec<PathBuf>,
+
+        /// Write results to a file, as FORMAT=PATH with FORMAT one of json,
+        /// html, markdown, csv, junit or console (e.g. "json=results.json");
+        /// may be repeated
+        #[arg(long = "out", value_name = "FORMAT=PATH")]
+        outputs: Vec<Output>,
     },
 
     /// Validate a scenario file
@@ -37,5 +60,161 @@ pub enum Commands {
         /// Path to the scenario file to validate
         #[arg(value_name = "SCENARIO")]
         scenario: PathBuf,
+
+        /// Read `${NAME}` variables from this .env file; may be repeated,
+        /// the process environment takes precedence
+        #[arg(long = "env-file", value_name = "PATH")]
+        env_files: Vec<PathBuf>,
+    },
+
+    /// Render a saved JSON result without running the test again
+    Report {
+        /// Result file written by `taran run --out json=PATH`
+        #[arg(value_name = "RESULTS")]
+        results: PathBuf,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Console)]
+        format: ReportFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
     },
+
+    /// Compare two saved JSON results and flag regressions
+    Compare {
+        /// Result of the reference run
+        #[arg(value_name = "BASELINE")]
+        baseline: PathBuf,
+
+        /// Result of the run to check
+        #[arg(value_name = "CANDIDATE")]
+        candidate: PathBuf,
+
+        /// Allowed latency increase, in percent
+        #[arg(long, value_name = "PERCENT", default_value_t = 10.0)]
+        latency_tolerance: f64,
+
+        /// Allowed throughput decrease, in percent
+        #[arg(long, value_name = "PERCENT", default_value_t = 10.0)]
+        rps_tolerance: f64,
+
+        /// Allowed error-rate increase, in percentage points
+        #[arg(long, value_name = "POINTS", default_value_t = 1.0)]
+        error_rate_tolerance: f64,
+
+        /// Significance level a latency increase must reach to count
+        #[arg(long, value_name = "P", default_value_t = 0.05)]
+        alpha: f64,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = CompareFormat::Console)]
+        format: CompareFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+}
+
+fn parse_duration(s: &str) -> Result<HumanDuration, String> {
+    HumanDuration::try_from(s.to_string())
+}
+
+/// Result file requested with `--out`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output {
+    pub format: ReportFormat,
+    pub path: PathBuf,
+}
+
+/// Formats results can be rendered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Json,
+    Html,
+    Markdown,
+    Csv,
+    Junit,
+    Console,
+}
+
+/// Formats a comparison can be rendered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompareFormat {
+    Console,
+    Markdown,
+}
+
+impl FromStr for Output {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (format, path) =
+            s.split_once('=').ok_or_else(|| format!("expected FORMAT=PATH, got '{s}'"))?;
+        if path.is_empty() {
+            return Err(format!("missing output path for '{format}'"));
+        }
+        let format = ReportFormat::from_str(format, true)?;
+        Ok(Self { format, path: PathBuf::from(path) })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_output() {
+        let cli =
+            Cli::try_parse_from(["taran", "run", "s.toml", "--out", "json=out/r.json"]).unwrap();
+        let Commands::Run { outputs, .. } = cli.command else { unreachable!() };
+        assert_eq!(outputs, [Output { format: ReportFormat::Json, path: "out/r.json".into() }]);
+        assert_eq!("junit=r.xml".parse::<Output>().unwrap().format, ReportFormat::Junit);
+
+        assert!("json".parse::<Output>().is_err());
+        assert!("json=".parse::<Output>().is_err());
+        assert!("xml=r.xml".parse::<Output>().is_err());
+    }
+
+    #[test]
+    fn test_parse_overrides() {
+        let args =
+            ["taran", "run", "s.toml", "-u", "5", "-d", "2m", "--set", "steps.0.url=http://x"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        let Commands::Run { users, duration, overrides, .. } = cli.command else { unreachable!() };
+        assert_eq!(users, Some(5));
+        assert_eq!(duration.unwrap().as_duration().as_secs(), 120);
+        assert_eq!(overrides[0].path, ["steps", "0", "url"]);
+
+        assert!(Cli::try_parse_from(["taran", "run", "s.toml", "-d", "soon"]).is_err());
+        assert!(Cli::try_parse_from(["taran", "run", "s.toml", "--set", "users"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let cli =
+            Cli::try_parse_from(["taran", "report", "r.json", "--format", "markdown"]).unwrap();
+        let Commands::Report { results, format, output } = cli.command else { unreachable!() };
+        assert_eq!((results, format, output), ("r.json".into(), ReportFormat::Markdown, None));
+
+        let cli = Cli::try_parse_from(["taran", "report", "r.json"]).unwrap();
+        assert!(matches!(cli.command, Commands::Report { format: ReportFormat::Console, .. }));
+        assert!(Cli::try_parse_from(["taran", "report", "r.json", "-f", "pdf"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_compare() {
+        let args =
+            ["taran", "compare", "a.json", "b.json", "--latency-tolerance", "5", "-f", "markdown"];
+        let cli = Cli::try_parse_from(args).unwrap();
+        let Commands::Compare { latency_tolerance, rps_tolerance, format, .. } = cli.command else {
+            unreachable!()
+        };
+        assert!((latency_tolerance - 5.0).abs() < f64::EPSILON);
+        assert!((rps_tolerance - 10.0).abs() < f64::EPSILON);
+        assert_eq!(format, CompareFormat::Markdown);
+    }
 }
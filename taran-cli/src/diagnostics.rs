@@ -0,0 +1,91 @@
+use anyhow::{bail, Result};
+use std::fmt::Write;
+use taran_config::{Diagnostic, Scenario};
+use taran_core::ProtocolRegistry;
+
+/// Check the scenario and print every problem found, failing if there is
+/// any
+pub fn check(config: &Scenario) -> Result<()> {
+    let diagnostics = config.diagnostics();
+    let protocols = ProtocolRegistry::default().check(config).err();
+
+    for diagnostic in &diagnostics {
+        eprintln!("{}", config.secrets.mask(&render(diagnostic)));
+    }
+    if let Some(error) = &protocols {
+        eprintln!("error: {}\n", config.secrets.mask(&error.to_string()));
+    }
+
+    match diagnostics.len() + usize::from(protocols.is_some()) {
+        0 => Ok(()),
+        1 => bail!("Scenario validation failed with 1 problem"),
+        count => bail!("Scenario validation failed with {count} problems"),
+    }
+}
+
+/// Render a diagnostic the way compilers do:
+///
+/// ```text
+/// error: Invalid scenario: steps[1].method 'GRAB' is not an HTTP method, ...
+///   --> shop.toml:10:10
+///    |
+/// 10 | method = "GRAB"
+///    |          ^
+/// ```
+pub fn render(diagnostic: &Diagnostic) -> String {
+    let mut rendered = format!("error: {}\n", diagnostic.error);
+    let Some(location) = &diagnostic.location else {
+        return rendered;
+    };
+    let gutter = " ".repeat(location.line.to_string().len());
+    let _ = writeln!(rendered, "{gutter}--> {location}");
+    let _ = writeln!(rendered, "{gutter} |");
+    let _ = writeln!(rendered, "{} | {}", location.line, location.text);
+    let indent: String = location
+        .text
+        .chars()
+        .take(location.column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let _ = writeln!(rendered, "{gutter} | {indent}^");
+    rendered
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use taran_config::{ConfigError, SourceLocation};
+
+    #[test]
+    fn test_render() {
+        let mut diagnostic = Diagnostic {
+            field: "steps[1].method".to_string(),
+            error: ConfigError::InvalidScenario("steps[1].method 'GRAB' is wrong".to_string()),
+            location: Some(SourceLocation {
+                file: Some("shop.toml".into()),
+                line: 10,
+                column: 10,
+                text: "method = \"GRAB\"".to_string(),
+            }),
+        };
+        assert_eq!(
+            render(&diagnostic),
+            [
+                "error: Invalid scenario: steps[1].method 'GRAB' is wrong",
+                "  --> shop.toml:10:10",
+                "   |",
+                "10 | method = \"GRAB\"",
+                "   |          ^",
+                "",
+            ]
+            .join("\n")
+        );
+
+        diagnostic.location = None;
+        assert_eq!(
+            render(&diagnostic),
+            "error: Invalid scenario: steps[1].method 'GRAB' is wrong\n"
+        );
+    }
+}
@@ -1,66 +1,180 @@
 mod cli;
+mod diagnostics;
+mod secrets;
 
 use anyhow::{Context, Result};
+use chrono::Utc;
 use clap::Parser;
-use cli::{Cli, Commands};
-use taran_config::Scenario;
+use cli::{Cli, Commands, CompareFormat, ReportFormat};
+use std::path::{Path, PathBuf};
+use taran_config::{Environment, Scenario};
 use taran_core::runner::TestRunner;
-use taran_report::ConsoleReporter;
+use taran_report::{
+    ConsoleReporter, CsvReporter, HtmlReporter, JsonReporter, JunitReporter, MarkdownReporter,
+    RunComparison, RunMetadata, RunReport, Tolerances,
+};
 use tracing::info;
 
+/// Exit code when a threshold failed; execution errors exit with 1
+const EXIT_THRESHOLDS_FAILED: i32 = 99;
+
+/// Exit code when `taran compare` found a regression
+const EXIT_REGRESSION: i32 = 99;
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let cli = Cli::parse();
 
     // Setup logging
     let log_level = if cli.verbose { "debug" } else { "info" };
-    tracing_subscriber::fmt().with_env_filter(log_level).init();
+    tracing_subscriber::fmt()
+        .with_env_filter(log_level)
+        .with_writer(|| secrets::MaskingWriter(std::io::stdout()))
+        .init();
+
+    // Errors can quote scenario values, e.g. a request URL, so they are
+    // masked like the logs instead of being returned from `main`
+    if let Err(err) = run(cli).await {
+        eprintln!("Error: {}", secrets::mask_error(&err));
+        std::process::exit(1);
+    }
+}
 
+async fn run(cli: Cli) -> Result<()> {
     match cli.command {
-        Commands::Run { scenario, users, duration } => {
+        Commands::Run { scenario, users, duration, overrides, env_files, outputs } => {
             info!("Loading scenario from: {}", scenario.display());
 
-            let config = Scenario::from_file(&scenario)
+            let env = load_env(&env_files)?;
+            let mut config = Scenario::load(&scenario, &env, &overrides)
                 .with_context(|| format!("Failed to load scenario from {}", scenario.display()))?;
+            secrets::mask_logs(&config.secrets);
+            config.override_load(users, duration).context("Invalid command-line override")?;
 
-            // Override configuration if specified
-            if let Some(user_count) = users {
-                info!("Overriding VU count to: {}", user_count);
-                // TODO: Override users in config
-            }
-
-            if let Some(dur) = duration {
-                info!("Overriding duration to: {}", dur);
-                // TODO: Override duration in config
-            }
-
-            config.validate().context("Scenario validation failed")?;
+            diagnostics::check(&config)?;
 
             info!("Running load test: {}", config.scenario.name);
 
-            let runner = TestRunner::new(config);
+            let has_thresholds = !config.thresholds.rules.is_empty();
+            let started_at = Utc::now();
+            let runner = TestRunner::new(config.clone());
             let summary = runner.run().await.context("Test execution failed")?;
+            let summary = secrets::mask(&config.secrets, summary)?;
+            let run = RunMetadata {
+                scenario: config.scenario.name.clone(),
+                description: config.scenario.description.clone(),
+                started_at,
+                finished_at: Utc::now(),
+            };
 
             let reporter = ConsoleReporter::new();
             reporter.print_summary(&summary).context("Failed to print summary")?;
 
-            // Exit with error code if there were failures
-            if summary.failed_requests > 0 {
-                std::process::exit(1);
+            let passed = summary.passed();
+            if !outputs.is_empty() {
+                let report = RunReport::new(run, &config, summary)
+                    .context("Failed to serialize the results")?;
+                let report = secrets::mask(&config.secrets, report)?;
+                for output in &outputs {
+                    write_report(&report, output.format, Some(&output.path))?;
+                    info!("Results written to {}", output.path.display());
+                }
+            }
+
+            if !passed {
+                std::process::exit(if has_thresholds { EXIT_THRESHOLDS_FAILED } else { 1 });
             }
         }
 
-        Commands::Validate { scenario } => {
+        Commands::Validate { scenario, env_files } => {
             info!("Validating scenario: {}", scenario.display());
 
-            let config = Scenario::from_file(&scenario)
+            let env = load_env(&env_files)?;
+            let config = Scenario::load(&scenario, &env, &[])
                 .with_context(|| format!("Failed to load scenario from {}", scenario.display()))?;
+            secrets::mask_logs(&config.secrets);
 
-            config.validate().context("Scenario validation failed")?;
+            diagnostics::check(&config)?;
 
             println!("✓ Scenario is valid");
         }
+
+        Commands::Report { results, format, output } => {
+            let report = RunReport::load(&results)
+                .with_context(|| format!("Failed to load results from {}", results.display()))?;
+            write_report(&report, format, output.as_deref())?;
+        }
+
+        Commands::Compare {
+            baseline,
+            candidate,
+            latency_tolerance,
+            rps_tolerance,
+            error_rate_tolerance,
+            alpha,
+            format,
+            output,
+        } => {
+            let load = |path: &Path| {
+                RunReport::load(path)
+                    .with_context(|| format!("Failed to load results from {}", path.display()))
+            };
+            let tolerances = Tolerances {
+                latency_pct: latency_tolerance,
+                rps_pct: rps_tolerance,
+                error_rate_points: error_rate_tolerance,
+                alpha,
+            };
+            let comparison = RunComparison::new(&load(&baseline)?, &load(&candidate)?, tolerances);
+            let rendered = match format {
+                CompareFormat::Console => comparison.console(),
+                CompareFormat::Markdown => comparison.markdown(),
+            };
+            write_output(&rendered, output.as_deref())?;
+
+            if comparison.has_regression() {
+                std::process::exit(EXIT_REGRESSION);
+            }
+        }
     }
 
     Ok(())
 }
+
+/// Render the report and write it to `path`, or to stdout without one
+fn write_report(report: &RunReport, format: ReportFormat, path: Option<&Path>) -> Result<()> {
+    let rendered = match format {
+        ReportFormat::Json => JsonReporter::new().render(report),
+        ReportFormat::Html => HtmlReporter::new().render(report),
+        ReportFormat::Markdown => Ok(MarkdownReporter::new().render(report)),
+        ReportFormat::Csv => CsvReporter::new().render(report),
+        ReportFormat::Junit => Ok(JunitReporter::new().render(report)),
+        ReportFormat::Console => Ok(ConsoleReporter::new().render(&report.summary)),
+    }
+    .context("Failed to render the report")?;
+
+    write_output(&rendered, path)
+}
+
+/// The process environment plus the `--env-file` variables
+fn load_env(env_files: &[PathBuf]) -> Result<Environment> {
+    let mut env = Environment::new();
+    for path in env_files {
+        env.load_file(path).context("Failed to load env file")?;
+    }
+    Ok(env)
+}
+
+/// Write `content` to `path`, or to stdout without one
+fn write_output(content: &str, path: Option<&Path>) -> Result<()> {
+    path.map_or_else(
+        || {
+            print!("{content}");
+            Ok(())
+        },
+        |path| {
+            std::fs::write(path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))
+        },
+    )
+}
@@ -0,0 +1,101 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io::Write;
+use std::sync::OnceLock;
+use taran_config::Secrets;
+
+/// Secrets of the loaded scenario, masked in every log line written after
+/// they are known
+static LOG_SECRETS: OnceLock<Secrets> = OnceLock::new();
+
+/// Mask `secrets` in the logs from now on
+pub fn mask_logs(secrets: &Secrets) {
+    let _ = LOG_SECRETS.set(secrets.clone());
+}
+
+/// The error and its causes on one line, with the secrets known so far
+/// masked
+pub fn mask_error(err: &anyhow::Error) -> String {
+    LOG_SECRETS.get().map_or_else(|| format!("{err:#}"), |secrets| error_message(secrets, err))
+}
+
+fn error_message(secrets: &Secrets, err: &anyhow::Error) -> String {
+    secrets.mask(&format!("{err:#}"))
+}
+
+/// Log writer masking the scenario secrets.
+///
+/// The fmt subscriber writes every event with a single `write_all`, so a
+/// secret is never split across two writes.
+pub struct MaskingWriter<W>(pub W);
+
+impl<W: Write> Write for MaskingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match LOG_SECRETS.get().filter(|secrets| !secrets.is_empty()) {
+            Some(secrets) => {
+                self.0.write_all(secrets.mask(&String::from_utf8_lossy(buf)).as_bytes())?;
+                Ok(buf.len())
+            }
+            None => self.0.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// `value` with every secret in its strings masked, e.g. a summary whose
+/// error messages quote a request URL
+pub fn mask<T: Serialize + DeserializeOwned>(secrets: &Secrets, value: T) -> Result<T> {
+    if secrets.is_empty() {
+        return Ok(value);
+    }
+    let mut json = serde_json::to_value(&value).context("Failed to mask secrets")?;
+    secrets.mask_json(&mut json);
+    serde_json::from_value(json).context("Failed to mask secrets")
+}
+
+#[cfg(test)]
+// `${secret:NAME}` looks like a format argument
+#[allow(clippy::unwrap_used, clippy::literal_string_with_formatting_args)]
+mod tests {
+    use super::*;
+    use taran_config::{Environment, Scenario, ScenarioFormat};
+    use taran_core::runner::TestRunner;
+
+    #[tokio::test]
+    async fn test_failed_run_does_not_echo_secrets() {
+        let toml = r#"
+[scenario]
+name = "Leaky"
+
+[load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+
+[[steps]]
+name = "Profile"
+protocol = "http"
+method = "GET"
+url = "https://example.com/me?token=${secret:TARAN_TEST_TOKEN}&page={{page"
+"#;
+        let mut env = Environment::new();
+        env.insert("TARAN_TEST_TOKEN", "s3cr3t-t0ken");
+        let config = Scenario::parse(toml, ScenarioFormat::Toml, &env, &[]).unwrap();
+
+        let err = TestRunner::new(config.clone())
+            .run()
+            .await
+            .context("Test execution failed")
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("s3cr3t-t0ken"));
+
+        let message = error_message(&config.secrets, &err);
+        assert!(message.starts_with("Test execution failed: "));
+        assert!(message.contains("token=***&page"));
+        assert!(!message.contains("s3cr3t"));
+    }
+}
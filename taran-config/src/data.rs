@@ -0,0 +1,230 @@
+use crate::error::{ConfigError, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A `[[data]]` file whose rows parameterize VU iterations.
+///
+/// Every column becomes a VU variable of the same name, so a CSV with a
+/// `username` header is referenced as `{{username}}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DataSource {
+    /// Label used in logs; defaults to the file name
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Path to the file, relative to the scenario file
+    pub file: PathBuf,
+    /// File format; inferred from the extension when omitted
+    #[serde(default)]
+    pub format: Option<DataFormat>,
+    #[serde(default)]
+    pub strategy: DataStrategy,
+    #[serde(default)]
+    pub on_exhausted: OnExhausted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// One JSON object per line
+    #[serde(alias = "ndjson")]
+    Jsonl,
+}
+
+/// How rows are handed out to iterations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataStrategy {
+    /// Rows in file order across all VUs, each used once
+    #[default]
+    Sequential,
+    /// A uniformly random row for every iteration
+    Random,
+    /// Each VU takes one row of its own and keeps it for its lifetime
+    UniquePerVu,
+    /// Rows in file order, starting over after the last one
+    Circular,
+}
+
+/// What happens when a sequential or unique-per-VU source runs out of rows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnExhausted {
+    /// The VU that found no row stops; other VUs carry on
+    #[default]
+    StopVu,
+    /// The whole test stops
+    StopTest,
+    /// Start over from the first row
+    Wrap,
+}
+
+/// Rows loaded from a data file, with values aligned to `columns`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataSet {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl DataSource {
+    /// Label used in logs and errors
+    pub fn label(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.file.display().to_string())
+    }
+
+    /// Resolve the file path against the scenario file's directory
+    pub fn resolve_path(&self, base_dir: Option<&Path>) -> PathBuf {
+        match base_dir {
+            Some(dir) if self.file.is_relative() => dir.join(&self.file),
+            _ => self.file.clone(),
+        }
+    }
+
+    /// Explicit format, or the one implied by the file extension
+    pub fn format(&self) -> Result<DataFormat> {
+        if let Some(format) = self.format {
+            return Ok(format);
+        }
+        match self.file.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("csv") => Ok(DataFormat::Csv),
+            Some("jsonl" | "ndjson") => Ok(DataFormat::Jsonl),
+            _ => {
+                Err(self
+                    .error("cannot infer format from extension, set format = \"csv\" or \"jsonl\""))
+            }
+        }
+    }
+
+    /// Read every row of the file
+    pub fn load(&self, base_dir: Option<&Path>) -> Result<DataSet> {
+        let path = self.resolve_path(base_dir);
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| self.error(&format!("cannot read {}: {e}", path.display())))?;
+
+        let data = match self.format()? {
+            DataFormat::Csv => self.parse_csv(&content)?,
+            DataFormat::Jsonl => self.parse_jsonl(&content)?,
+        };
+        if data.rows.is_empty() {
+            return Err(self.error("file contains no rows"));
+        }
+        Ok(data)
+    }
+
+    fn parse_csv(&self, content: &str) -> Result<DataSet> {
+        let mut reader = csv::Reader::from_reader(content.as_bytes());
+        let columns: Vec<String> = reader
+            .headers()
+            .map_err(|e| self.error(&format!("invalid CSV header: {e}")))?
+            .iter()
+            .map(|h| h.trim().to_string())
+            .collect();
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|e| self.error(&format!("invalid CSV row: {e}")))?;
+            rows.push(record.iter().map(str::to_string).collect());
+        }
+        Ok(DataSet { columns, rows })
+    }
+
+    fn parse_jsonl(&self, content: &str) -> Result<DataSet> {
+        let mut columns: Vec<String> = Vec::new();
+        let mut objects = Vec::new();
+        for (i, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let value: serde_json::Value = serde_json::from_str(line)
+                .map_err(|e| self.error(&format!("line {}: {e}", i + 1)))?;
+            let serde_json::Value::Object(object) = value else {
+                return Err(self.error(&format!("line {}: expected a JSON object", i + 1)));
+            };
+            for key in object.keys() {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+            objects.push(object);
+        }
+
+        // Keys missing from a line become empty strings so every row has every column
+        let rows = objects
+            .iter()
+            .map(|object| {
+                columns
+                    .iter()
+                    .map(|column| match object.get(column) {
+                        Some(serde_json::Value::String(s)) => s.clone(),
+                        Some(other) => other.to_string(),
+                        None => String::new(),
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(DataSet { columns, rows })
+    }
+
+    fn error(&self, reason: &str) -> ConfigError {
+        ConfigError::DataFile { name: self.label(), reason: reason.to_string() }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn source(file: &Path) -> DataSource {
+        DataSource {
+            name: None,
+            file: file.to_path_buf(),
+            format: None,
+            strategy: DataStrategy::default(),
+            on_exhausted: OnExhausted::default(),
+        }
+    }
+
+    fn write_file(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::File::create(&path).unwrap().write_all(content.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_load_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        write_file(dir.path(), "users.csv", "username,password\nalice,a1\nbob,\"b,2\"\n");
+        let data = source(Path::new("users.csv")).load(Some(dir.path())).unwrap();
+        assert_eq!(data.columns, ["username", "password"]);
+        assert_eq!(data.rows, [["alice", "a1"], ["bob", "b,2"]]);
+    }
+
+    #[test]
+    fn test_load_jsonl() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_file(
+            dir.path(),
+            "users.jsonl",
+            "{\"id\": 1, \"name\": \"alice\"}\n\n{\"name\": \"bob\", \"admin\": true}\n",
+        );
+        let data = source(&path).load(None).unwrap();
+        assert_eq!(data.columns, ["id", "name", "admin"]);
+        assert_eq!(data.rows, [["1", "alice", ""], ["", "bob", "true"]]);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let empty = write_file(dir.path(), "empty.csv", "username\n");
+        assert!(source(&empty).load(None).is_err());
+        let unknown = write_file(dir.path(), "users.txt", "username\nalice\n");
+        assert!(source(&unknown).load(None).is_err());
+        let not_object = write_file(dir.path(), "bad.jsonl", "[1, 2]\n");
+        assert!(source(&not_object).load(None).is_err());
+        assert!(source(&dir.path().join("missing.csv")).load(None).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use crate::duration::HumanDuration;
+use crate::scenario::{Assertions, Step};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// `[defaults]`: settings every step inherits unless it sets its own
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StepDefaults {
+    /// Prefix of every step URL without a scheme, e.g. `/login`
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Sent with every request; a step header of the same name wins
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Request timeout of steps without one
+    #[serde(default)]
+    pub timeout: Option<HumanDuration>,
+    /// Checked on every response; a step's own assertions win field by field
+    #[serde(default)]
+    pub assertions: Option<Assertions>,
+}
+
+impl StepDefaults {
+    /// Fill in what `step` leaves out
+    pub fn apply(&self, step: &mut Step) {
+        if let Some(base_url) = &self.base_url {
+            if is_relative(&step.url) {
+                step.url = join_url(base_url, &step.url);
+            }
+        }
+
+        for (name, value) in &self.headers {
+            if !step.headers.keys().any(|own| own.eq_ignore_ascii_case(name)) {
+                step.headers.insert(name.clone(), value.clone());
+            }
+        }
+
+        step.timeout = step.timeout.or(self.timeout);
+
+        if let Some(defaults) = &self.assertions {
+            let assertions = step.assertions.get_or_insert_with(|| defaults.clone());
+            assertions.status = assertions.status.or(defaults.status);
+            assertions.max_response_time =
+                assertions.max_response_time.or(defaults.max_response_time);
+            if assertions.body_contains.is_none() {
+                assertions.body_contains.clone_from(&defaults.body_contains);
+            }
+        }
+    }
+}
+
+/// A URL without a scheme that does not start with a placeholder, which
+/// may render to a full URL
+fn is_relative(url: &str) -> bool {
+    !url.contains("://") && !url.trim_start().starts_with("{{")
+}
+
+fn join_url(base_url: &str, path: &str) -> String {
+    if path.is_empty() {
+        return base_url.to_string();
+    }
+    format!("{}/{}", base_url.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::Scenario;
+    use std::time::Duration;
+
+    #[test]
+    fn test_steps_inherit_defaults() {
+        let scenario = Scenario::from_toml(
+            r#"
+[scenario]
+name = "Defaults"
+
+[defaults]
+base_url = "https://api.example.com/v1/"
+timeout = "5s"
+headers = { Accept = "application/json", "X-Client" = "taran" }
+assertions = { status = 200, max_response_time = "1s" }
+
+[load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+
+[[steps]]
+name = "Login"
+protocol = "http"
+method = "POST"
+url = "/login"
+headers = { accept = "text/plain" }
+assertions = { status = 201 }
+
+[[steps]]
+name = "Health"
+protocol = "http"
+method = "GET"
+url = "http://localhost:9000/health"
+timeout = "200ms"
+
+[[steps]]
+name = "Next"
+protocol = "http"
+method = "GET"
+url = "{{next_url}}"
+"#,
+        )
+        .unwrap();
+        let [login, health, next] = &scenario.steps[..] else { unreachable!() };
+
+        assert_eq!(login.url, "https://api.example.com/v1/login");
+        assert_eq!(login.headers.len(), 2);
+        assert_eq!(login.headers["accept"], "text/plain");
+        assert_eq!(login.headers["X-Client"], "taran");
+        assert_eq!(login.timeout.unwrap().as_duration(), Duration::from_secs(5));
+        let assertions = login.assertions.as_ref().unwrap();
+        assert_eq!(assertions.status, Some(201));
+        assert_eq!(assertions.max_response_time.unwrap().as_duration(), Duration::from_secs(1));
+
+        assert_eq!(health.url, "http://localhost:9000/health");
+        assert_eq!(health.timeout.unwrap().as_duration(), Duration::from_millis(200));
+        assert_eq!(health.assertions.as_ref().unwrap().status, Some(200));
+        assert_eq!(next.url, "{{next_url}}");
+    }
+
+    #[test]
+    fn test_defaults_apply_to_named_scenarios() {
+        let scenario = Scenario::from_toml(
+            r#"
+[scenario]
+name = "Defaults"
+
+[defaults]
+base_url = "https://shop.example.com"
+
+[[scenarios]]
+name = "browse"
+load_profile = { type = "constant", users = 1, duration = "1s" }
+steps = [{ name = "Home", protocol = "http", method = "GET", url = "" }]
+"#,
+        )
+        .unwrap();
+        assert_eq!(scenario.scenarios[0].steps[0].url, "https://shop.example.com");
+    }
+}
@@ -0,0 +1,318 @@
+use crate::error::ConfigError;
+use crate::format::ScenarioFormat;
+use serde::de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+use std::path::PathBuf;
+
+/// One problem found by [`Scenario::diagnostics`](crate::Scenario::diagnostics)
+#[derive(Debug)]
+pub struct Diagnostic {
+    /// Path of the offending field, e.g. `steps[1].method`
+    pub field: String,
+    pub error: ConfigError,
+    /// Where the field is written, when the scenario was loaded from source
+    pub location: Option<SourceLocation>,
+}
+
+/// Position of a field in a scenario file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// The file, `None` for content parsed from a string
+    pub file: Option<PathBuf>,
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    /// The whole source line, for showing the problem in context
+    pub text: String,
+}
+
+/// Source files a scenario was loaded from, so diagnostics can point into
+/// the right one after includes were merged
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    /// The scenario file first, then every included file
+    pub(crate) files: Vec<SourceFile>,
+    /// `(file, index in its steps)` of every top-level step; empty when the
+    /// steps all come from the scenario file
+    pub(crate) steps: Vec<(usize, usize)>,
+    /// The same for `[[data]]` sources
+    pub(crate) data: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SourceFile {
+    pub path: Option<PathBuf>,
+    pub content: String,
+    pub format: ScenarioFormat,
+}
+
+impl SourceMap {
+    pub(crate) fn new(path: Option<PathBuf>, content: &str, format: ScenarioFormat) -> Self {
+        Self {
+            files: vec![SourceFile { path, content: content.to_string(), format }],
+            ..Self::default()
+        }
+    }
+
+    /// Where `field` is written; falls back to the closest parent that can
+    /// be found, e.g. the step of a missing `steps[0].url`
+    pub fn locate(&self, field: &str) -> Option<SourceLocation> {
+        let mut path = parse_field(field);
+        // Included steps and data sources live in their own file
+        let mut file = 0;
+        if let [Segment::Key(key), Segment::Index(index), ..] = &mut path[..] {
+            let origins = match key.as_str() {
+                "steps" => &self.steps,
+                "data" => &self.data,
+                _ => &Vec::new(),
+            };
+            if let Some(&(origin, original)) = origins.get(*index) {
+                (file, *index) = (origin, original);
+            }
+        }
+
+        let source = self.files.get(file)?;
+        (0..=path.len()).rev().find_map(|len| {
+            let offset = find(&source.content, source.format, &path[..len])?;
+            Some(source.location(offset))
+        })
+    }
+}
+
+impl SourceFile {
+    fn location(&self, position: Position) -> SourceLocation {
+        let (line, column) = match position {
+            Position::Offset(offset) => {
+                let before = &self.content[..offset.min(self.content.len())];
+                let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+                (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+            }
+            Position::LineColumn(line, column) => (line, column),
+        };
+        SourceLocation {
+            file: self.path.clone(),
+            line,
+            column,
+            text: self.content.lines().nth(line.saturating_sub(1)).unwrap_or_default().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// `steps[1].headers.Accept` → `steps`, `1`, `headers`, `Accept`
+fn parse_field(field: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    for part in field.split('.') {
+        let mut rest = part;
+        if let Some(open) = rest.find('[') {
+            segments.push(Segment::Key(rest[..open].to_string()));
+            rest = &rest[open..];
+            while let Some(index) = rest.strip_prefix('[').and_then(|r| r.split_once(']')) {
+                match index.0.parse() {
+                    Ok(i) => segments.push(Segment::Index(i)),
+                    Err(_) => segments.push(Segment::Key(index.0.to_string())),
+                }
+                rest = index.1;
+            }
+        } else {
+            segments.push(Segment::Key(rest.to_string()));
+        }
+    }
+    segments
+}
+
+#[derive(Clone, Copy)]
+enum Position {
+    /// Byte offset into the content
+    Offset(usize),
+    /// 1-based line and column
+    LineColumn(usize, usize),
+}
+
+/// Marker in the error raised once the wanted value is reached
+const FOUND: &str = "\u{0}located";
+
+/// Find where the value at `path` starts by walking the document with the
+/// format's own deserializer and stopping at that value with an error,
+/// which carries its position
+fn find(content: &str, format: ScenarioFormat, path: &[Segment]) -> Option<Position> {
+    let seed = Locate { path };
+    match format {
+        ScenarioFormat::Toml => {
+            let error = seed.deserialize(toml::Deserializer::new(content)).err()?;
+            error.message().contains(FOUND).then_some(())?;
+            error.span().map(|span| Position::Offset(span.start))
+        }
+        ScenarioFormat::Yaml => {
+            let error = seed.deserialize(serde_yaml::Deserializer::from_str(content)).err()?;
+            error.to_string().contains(FOUND).then_some(())?;
+            error.location().map(|l| Position::LineColumn(l.line(), l.column()))
+        }
+        ScenarioFormat::Json => {
+            let mut deserializer = serde_json::Deserializer::from_str(content);
+            let error = seed.deserialize(&mut deserializer).err()?;
+            error.to_string().contains(FOUND).then_some(())?;
+            Some(Position::LineColumn(error.line(), error.column()))
+        }
+    }
+}
+
+/// Descends into the value at `path`; every other value is skipped
+struct Locate<'a> {
+    path: &'a [Segment],
+}
+
+impl<'de> DeserializeSeed<'de> for Locate<'_> {
+    type Value = ();
+
+    fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        if self.path.is_empty() {
+            // The default `visit_*` methods all fail with `expecting` in the
+            // message, wherever the value is
+            deserializer.deserialize_any(Found)
+        } else {
+            deserializer.deserialize_any(self)
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for Locate<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a table or array")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let (Segment::Key(wanted), rest) = (&self.path[0], &self.path[1..]) else {
+            return Ok(());
+        };
+        while let Some(key) = map.next_key::<String>()? {
+            if key == *wanted {
+                map.next_value_seed(Locate { path: rest })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let (Segment::Index(wanted), rest) = (&self.path[0], &self.path[1..]) else {
+            return Ok(());
+        };
+        let mut index = 0;
+        loop {
+            let more = if index == *wanted {
+                seq.next_element_seed(Locate { path: rest })?.is_some()
+            } else {
+                seq.next_element::<IgnoredAny>()?.is_some()
+            };
+            if !more {
+                return Ok(());
+            }
+            index += 1;
+        }
+    }
+}
+
+struct Found;
+
+impl Visitor<'_> for Found {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(FOUND)
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"[scenario]
+name = "Shop"
+
+[[steps]]
+name = "Home"
+url = "https://example.com/"
+
+[[steps]]
+name = "Login"
+method = "GRAB"
+headers = { Accept = "text/html" }
+"#;
+
+    fn locate(content: &str, format: ScenarioFormat, field: &str) -> (usize, usize) {
+        let location = SourceMap::new(None, content, format).locate(field).unwrap();
+        (location.line, location.column)
+    }
+
+    #[test]
+    fn test_parse_field() {
+        assert_eq!(
+            parse_field("scenarios[2].steps[0].extract.token"),
+            [
+                Segment::Key("scenarios".to_string()),
+                Segment::Index(2),
+                Segment::Key("steps".to_string()),
+                Segment::Index(0),
+                Segment::Key("extract".to_string()),
+                Segment::Key("token".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_locate_toml() {
+        assert_eq!(locate(TOML, ScenarioFormat::Toml, "steps[1].method"), (10, 10));
+        assert_eq!(locate(TOML, ScenarioFormat::Toml, "steps[1].headers.Accept"), (11, 22));
+        // A missing field points at its parent
+        assert_eq!(locate(TOML, ScenarioFormat::Toml, "steps[1].url").0, 8);
+        let location = SourceMap::new(Some("shop.toml".into()), TOML, ScenarioFormat::Toml)
+            .locate("scenario.name")
+            .unwrap();
+        assert_eq!(location.to_string(), "shop.toml:2:8");
+        assert_eq!(location.text, "name = \"Shop\"");
+    }
+
+    #[test]
+    fn test_locate_yaml_and_json() {
+        let yaml =
+            "scenario:\n  name: Shop\nsteps:\n  - name: Home\n  - name: Login\n    method: GRAB\n";
+        assert_eq!(locate(yaml, ScenarioFormat::Yaml, "steps[1].method"), (6, 13));
+
+        let json = "{\n  \"steps\": [\n    {},\n    { \"method\": \"GRAB\" }\n  ]\n}";
+        assert_eq!(locate(json, ScenarioFormat::Json, "steps[1].method").0, 4);
+    }
+
+    #[test]
+    fn test_locate_included_steps() {
+        let mut sources = SourceMap::new(None, TOML, ScenarioFormat::Toml);
+        sources.files.push(SourceFile {
+            path: Some("common/auth.yaml".into()),
+            content: "steps:\n  - name: Auth\n    method: GRAB\n".to_string(),
+            format: ScenarioFormat::Yaml,
+        });
+        sources.steps = vec![(1, 0), (0, 0), (0, 1)];
+        let location = sources.locate("steps[0].method").unwrap();
+        assert_eq!(location.to_string(), "common/auth.yaml:3:13");
+        assert_eq!(sources.locate("steps[2].method").unwrap().line, 10);
+    }
+}
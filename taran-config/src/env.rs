@@ -0,0 +1,303 @@
+use crate::error::{ConfigError, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// What a secret is replaced with in logs and reports
+pub const MASK: &str = "***";
+
+/// Variables `${NAME}` references in a scenario expand to.
+///
+/// The process environment comes first, then the env files in the order
+/// they were loaded, a later file overriding an earlier one — the same
+/// precedence as `docker compose`.
+#[derive(Debug, Clone, Default)]
+pub struct Environment {
+    file_vars: HashMap<String, String>,
+}
+
+impl Environment {
+    /// The process environment only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the variables of a `.env` file
+    pub fn load_file(&mut self, path: &Path) -> Result<()> {
+        let invalid = |e: dotenvy::Error| ConfigError::EnvFile {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        };
+        for item in dotenvy::from_path_iter(path).map_err(invalid)? {
+            let (name, value) = item.map_err(invalid)?;
+            self.file_vars.insert(name, value);
+        }
+        Ok(())
+    }
+
+    /// Set a variable as if it came from an env file
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.file_vars.insert(name.into(), value.into());
+    }
+
+    pub fn get(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok().or_else(|| self.file_vars.get(name).cloned())
+    }
+
+    /// Expand the references in every string of a parsed scenario document,
+    /// collecting the values marked `${secret:NAME}`
+    pub(crate) fn interpolate(
+        &self,
+        document: &mut serde_json::Value,
+        secrets: &mut Secrets,
+    ) -> Result<()> {
+        self.interpolate_at(document, &mut Vec::new(), secrets)
+    }
+
+    fn interpolate_at(
+        &self,
+        value: &mut serde_json::Value,
+        path: &mut Vec<String>,
+        secrets: &mut Secrets,
+    ) -> Result<()> {
+        match value {
+            serde_json::Value::String(text) if text.contains('$') => {
+                *text = self.expand(text, &path.join("."), secrets)?;
+            }
+            serde_json::Value::Array(items) => {
+                for (index, item) in items.iter_mut().enumerate() {
+                    path.push(index.to_string());
+                    self.interpolate_at(item, path, secrets)?;
+                    path.pop();
+                }
+            }
+            serde_json::Value::Object(table) => {
+                for (key, item) in table.iter_mut() {
+                    path.push(key.clone());
+                    self.interpolate_at(item, path, secrets)?;
+                    path.pop();
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Expand `${NAME}`, `${NAME:-default}` and their `${secret:…}` forms;
+    /// `$${` is a literal `${`
+    fn expand(&self, text: &str, field: &str, secrets: &mut Secrets) -> Result<String> {
+        let invalid =
+            |reason: String| ConfigError::InvalidEnvReference { field: field.to_string(), reason };
+
+        let mut expanded = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(dollar) = rest.find('$') {
+            expanded.push_str(&rest[..dollar]);
+            let after = &rest[dollar + 1..];
+            if let Some(escaped) = after.strip_prefix("${") {
+                expanded.push_str("${");
+                rest = escaped;
+                continue;
+            }
+            let Some(reference) = after.strip_prefix('{') else {
+                expanded.push('$');
+                rest = after;
+                continue;
+            };
+            let close = reference.find('}').ok_or_else(|| invalid("unclosed '${'".to_string()))?;
+            rest = &reference[close + 1..];
+            let reference = &reference[..close];
+
+            let (secret, reference) = reference
+                .strip_prefix("secret:")
+                .map_or((false, reference), |reference| (true, reference));
+            let (name, default) = match reference.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (reference, None),
+            };
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(invalid(format!("invalid variable name '{name}'")));
+            }
+            // Like the shell, a default also replaces an empty value
+            let value = match (self.get(name), default) {
+                (Some(value), Some(default)) if value.is_empty() => default.to_string(),
+                (Some(value), _) => value,
+                (None, Some(default)) => default.to_string(),
+                (None, None) => {
+                    return Err(invalid(format!(
+                        "environment variable {name} is not set and has no default"
+                    )))
+                }
+            };
+            if secret {
+                secrets.insert(value.clone());
+            }
+            expanded.push_str(&value);
+        }
+        expanded.push_str(rest);
+        Ok(expanded)
+    }
+}
+
+/// Values a scenario marked `${secret:NAME}`, to be masked wherever the
+/// run could show them.
+///
+/// `Debug` only prints how many there are.
+#[derive(Clone, Default)]
+pub struct Secrets {
+    /// Longest first, so a secret containing another is masked whole
+    values: Vec<String>,
+}
+
+impl Secrets {
+    pub fn insert(&mut self, value: String) {
+        if value.is_empty() || self.values.contains(&value) {
+            return;
+        }
+        self.values.push(value);
+        self.values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Replace every secret in `text` with [`MASK`]
+    pub fn mask(&self, text: &str) -> String {
+        self.values
+            .iter()
+            .fold(text.to_string(), |text, secret| text.replace(secret.as_str(), MASK))
+    }
+
+    /// Mask every string and object key of a JSON value
+    pub fn mask_json(&self, value: &mut serde_json::Value) {
+        if self.is_empty() {
+            return;
+        }
+        match value {
+            serde_json::Value::String(text) => *text = self.mask(text),
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.mask_json(item);
+                }
+            }
+            serde_json::Value::Object(table) => {
+                *table = std::mem::take(table)
+                    .into_iter()
+                    .map(|(key, mut item)| {
+                        self.mask_json(&mut item);
+                        (self.mask(&key), item)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl std::fmt::Debug for Secrets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Secrets({} values)", self.values.len())
+    }
+}
+
+#[cfg(test)]
+// `${secret:NAME}` looks like a format argument
+#[allow(clippy::unwrap_used, clippy::literal_string_with_formatting_args)]
+mod tests {
+    use super::*;
+    use crate::{ScenarioFormat, Step};
+
+    fn env() -> Environment {
+        let mut env = Environment::new();
+        env.insert("TARAN_TEST_HOST", "staging.example.com");
+        env.insert("TARAN_TEST_TOKEN", "s3cr3t-t0ken");
+        env.insert("TARAN_TEST_EMPTY", "");
+        env
+    }
+
+    fn expand(text: &str) -> Result<(String, Secrets)> {
+        let mut secrets = Secrets::default();
+        let expanded = env().expand(text, "steps.0.url", &mut secrets)?;
+        Ok((expanded, secrets))
+    }
+
+    #[test]
+    fn test_expand() {
+        let (url, secrets) = expand("https://${TARAN_TEST_HOST}/api?v=$1").unwrap();
+        assert_eq!(url, "https://staging.example.com/api?v=$1");
+        assert!(secrets.is_empty());
+        assert_eq!(expand("${TARAN_TEST_UNSET:-8080}").unwrap().0, "8080");
+        assert_eq!(expand("${TARAN_TEST_EMPTY:-fallback}").unwrap().0, "fallback");
+        assert_eq!(expand("[${TARAN_TEST_EMPTY}]").unwrap().0, "[]");
+        assert_eq!(
+            expand("$${TARAN_TEST_HOST} {{user}}").unwrap().0,
+            "${TARAN_TEST_HOST} {{user}}"
+        );
+
+        let (header, secrets) = expand("Bearer ${secret:TARAN_TEST_TOKEN}").unwrap();
+        assert_eq!(header, "Bearer s3cr3t-t0ken");
+        assert_eq!(secrets.mask(&header), "Bearer ***");
+        assert_eq!(format!("{secrets:?}"), "Secrets(1 values)");
+
+        let err = expand("${TARAN_TEST_UNSET}").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Cannot expand steps.0.url: environment variable TARAN_TEST_UNSET is not set and \
+             has no default"
+        );
+        assert!(expand("${TARAN_TEST_HOST").is_err());
+        assert!(expand("${not a name}").is_err());
+    }
+
+    #[test]
+    fn test_process_environment_wins() {
+        let mut env = Environment::new();
+        env.insert("PATH", "from-file");
+        assert_ne!(env.get("PATH").as_deref(), Some("from-file"));
+    }
+
+    #[test]
+    fn test_env_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env");
+        std::fs::write(&path, "# staging\nTARAN_TEST_HOST=a\nTARAN_TEST_TOKEN=\"quoted value\"\n")
+            .unwrap();
+        let mut env = Environment::new();
+        env.load_file(&path).unwrap();
+        assert_eq!(env.get("TARAN_TEST_TOKEN").as_deref(), Some("quoted value"));
+
+        std::fs::write(&path, "TARAN_TEST_HOST=b\n").unwrap();
+        env.load_file(&path).unwrap();
+        assert_eq!(env.get("TARAN_TEST_HOST").as_deref(), Some("b"));
+        assert!(env.load_file(&dir.path().join("missing.env")).is_err());
+    }
+
+    #[test]
+    fn test_scenario_interpolation() {
+        let toml = r#"
+[scenario]
+name = "Env"
+
+[load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+
+[[steps]]
+name = "Profile"
+protocol = "http"
+method = "GET"
+url = "https://${TARAN_TEST_HOST}/me"
+headers = { Authorization = "Bearer ${secret:TARAN_TEST_TOKEN}" }
+"#;
+        let scenario = crate::Scenario::parse(toml, ScenarioFormat::Toml, &env(), &[]).unwrap();
+        let step: &Step = &scenario.steps[0];
+        assert_eq!(step.url, "https://staging.example.com/me");
+        assert_eq!(step.headers["Authorization"], "Bearer s3cr3t-t0ken");
+
+        let mut exported = serde_json::to_value(&scenario).unwrap();
+        scenario.secrets.mask_json(&mut exported);
+        assert_eq!(exported["steps"][0]["headers"]["Authorization"], "Bearer ***");
+        assert!(!exported.to_string().contains("s3cr3t"));
+    }
+}
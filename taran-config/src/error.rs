@@ -8,6 +8,15 @@ pub enum ConfigError {
     #[error("Failed to parse TOML: {0}")]
     TomlParse(#[from] toml::de::Error),
 
+    #[error("Failed to parse YAML{}: {message}", location(*.line, *.column))]
+    YamlParse { line: Option<usize>, column: Option<usize>, message: String },
+
+    #[error("Failed to parse JSON{}: {message}", location(*.line, *.column))]
+    JsonParse { line: Option<usize>, column: Option<usize>, message: String },
+
+    #[error("Unsupported scenario file {0}: expected a .toml, .yaml, .yml or .json extension")]
+    UnsupportedFormat(String),
+
     #[error("Invalid scenario: {0}")]
     InvalidScenario(String),
 
@@ -16,6 +25,36 @@ pub enum ConfigError {
 
     #[error("Invalid duration format: {0}")]
     InvalidDuration(String),
+
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
+
+    #[error("Invalid data source '{name}': {reason}")]
+    DataFile { name: String, reason: String },
+
+    #[error("Invalid threshold '{expression}': {reason}")]
+    InvalidThreshold { expression: String, reason: String },
+
+    #[error("Invalid include {path}: {reason}")]
+    Include { path: String, reason: String },
+
+    #[error("Cannot expand {field}: {reason}")]
+    InvalidEnvReference { field: String, reason: String },
+
+    #[error("Invalid env file {path}: {reason}")]
+    EnvFile { path: String, reason: String },
+
+    #[error("Cannot override {field}: {reason}")]
+    InvalidOverride { field: String, reason: String },
+}
+
+/// ` at line L, column C` when the position is known
+fn location(line: Option<usize>, column: Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!(" at line {line}, column {column}"),
+        (Some(line), None) => format!(" at line {line}"),
+        _ => String::new(),
+    }
 }
 
 pub type Result<T> = std::result::Result<T, ConfigError>;
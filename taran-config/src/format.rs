@@ -0,0 +1,197 @@
+use crate::error::{ConfigError, Result};
+use serde::de::{DeserializeOwned, Error as _};
+use std::path::Path;
+
+/// File format of a scenario, picked from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioFormat {
+    /// `.toml`
+    Toml,
+    /// `.yaml` or `.yml`
+    Yaml,
+    /// `.json`
+    Json,
+}
+
+impl ScenarioFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_lowercase).as_deref() {
+            Some("toml") => Ok(Self::Toml),
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            Some("json") => Ok(Self::Json),
+            _ => Err(ConfigError::UnsupportedFormat(path.display().to_string())),
+        }
+    }
+
+    /// Deserialize `content`, reporting errors with their line and column
+    pub fn parse<T: DeserializeOwned>(self, content: &str) -> Result<T> {
+        match self {
+            Self::Toml => toml::from_str(content).map_err(ConfigError::TomlParse),
+            Self::Yaml => serde_yaml::from_str(content).map_err(|e| {
+                let (line, column) = e.location().map(|l| (l.line(), l.column())).unzip();
+                ConfigError::YamlParse { line, column, message: without_position(&e, line, column) }
+            }),
+            Self::Json => serde_json::from_str(content).map_err(|e| {
+                let (line, column) = (Some(e.line()), Some(e.column()));
+                ConfigError::JsonParse { line, column, message: without_position(&e, line, column) }
+            }),
+        }
+    }
+
+    /// Deserialize a document that was already parsed from this format.
+    ///
+    /// Positions in the source are gone by now, so errors only carry the
+    /// message.
+    pub(crate) fn deserialize_document<T: DeserializeOwned>(
+        self,
+        document: serde_json::Value,
+    ) -> Result<T> {
+        T::deserialize(document).map_err(|e| match self {
+            Self::Toml => ConfigError::TomlParse(toml::de::Error::custom(e)),
+            Self::Yaml => {
+                ConfigError::YamlParse { line: None, column: None, message: e.to_string() }
+            }
+            Self::Json => {
+                ConfigError::JsonParse { line: None, column: None, message: e.to_string() }
+            }
+        })
+    }
+}
+
+/// The error message without the ` at line L column C` suffix `serde_json`
+/// and `serde_yaml` append, the error variants carry the position themselves
+fn without_position(
+    error: &impl std::fmt::Display,
+    line: Option<usize>,
+    column: Option<usize>,
+) -> String {
+    let message = error.to_string();
+    let (Some(line), Some(column)) = (line, column) else { return message };
+    message
+        .strip_suffix(&format!(" at line {line} column {column}"))
+        .map_or_else(|| message.clone(), str::to_string)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::{Environment, LoadProfile, Scenario};
+
+    const TOML: &str = r#"
+[scenario]
+name = "Checkout"
+
+[load_profile]
+type = "ramp"
+from = 1
+to = 20
+duration = "2m"
+
+[[steps]]
+name = "Home"
+protocol = "http"
+method = "GET"
+url = "https://example.com/"
+headers = { Accept = "text/html" }
+"#;
+
+    const YAML: &str = r"
+scenario:
+  name: Checkout
+load_profile:
+  type: ramp
+  from: 1
+  to: 20
+  duration: 2m
+steps:
+  - name: Home
+    protocol: http
+    method: GET
+    url: https://example.com/
+    headers:
+      Accept: text/html
+";
+
+    const JSON: &str = r#"{
+  "scenario": { "name": "Checkout" },
+  "load_profile": { "type": "ramp", "from": 1, "to": 20, "duration": "2m" },
+  "steps": [
+    {
+      "name": "Home",
+      "protocol": "http",
+      "method": "GET",
+      "url": "https://example.com/",
+      "headers": { "Accept": "text/html" }
+    }
+  ]
+}"#;
+
+    #[test]
+    fn test_format_from_extension() {
+        let format = |path: &str| ScenarioFormat::from_path(Path::new(path));
+        assert_eq!(format("load.toml").unwrap(), ScenarioFormat::Toml);
+        assert_eq!(format("load.YAML").unwrap(), ScenarioFormat::Yaml);
+        assert_eq!(format("dir/load.yml").unwrap(), ScenarioFormat::Yaml);
+        assert_eq!(format("load.json").unwrap(), ScenarioFormat::Json);
+        let err = format("load.ini").unwrap_err().to_string();
+        assert!(err.contains("expected a .toml, .yaml, .yml or .json extension"), "{err}");
+        assert!(format("load").is_err());
+    }
+
+    #[test]
+    fn test_formats_are_equivalent() {
+        let expected = serde_json::to_value(Scenario::from_toml(TOML).unwrap()).unwrap();
+        for (content, format) in [(YAML, ScenarioFormat::Yaml), (JSON, ScenarioFormat::Json)] {
+            let scenario = Scenario::parse(content, format, &Environment::new(), &[]).unwrap();
+            scenario.validate().unwrap();
+            assert_eq!(serde_json::to_value(scenario).unwrap(), expected, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn test_errors_carry_line_and_column() {
+        let err = Scenario::from_yaml("scenario:\n  name: \"oops\n").unwrap_err();
+        let ConfigError::YamlParse { line, column, message } = &err else { unreachable!() };
+        assert_eq!((*line, *column), (Some(3), Some(1)));
+        assert!(message.contains("while scanning a quoted scalar"), "{message}");
+
+        let yaml = YAML.replace("url: https://example.com/", "url: [oops]");
+        let err = Scenario::from_yaml(&yaml).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Failed to parse YAML at line 13, column 10: steps[0].url: invalid type: sequence, \
+             expected a string"
+        );
+
+        let json = JSON.replace("\"from\": 1,", "\"from\": 1");
+        let err = Scenario::from_json(&json).unwrap_err();
+        let ConfigError::JsonParse { line, column, message } = &err else { unreachable!() };
+        assert_eq!((*line, *column), (Some(3), Some(47)));
+        assert_eq!(message, "expected `,` or `}`");
+        assert!(err.to_string().starts_with("Failed to parse JSON at line 3, column 47: "));
+    }
+
+    #[test]
+    fn test_overrides_apply_to_every_format() {
+        let overrides: Vec<crate::Override> = ["load_profile.to=5", "http.client=shared"]
+            .iter()
+            .map(|o| o.parse().unwrap())
+            .collect();
+        // An empty YAML key is null and can still be patched
+        let yaml = format!("{YAML}http:\n");
+        for (content, format) in
+            [(yaml.as_str(), ScenarioFormat::Yaml), (JSON, ScenarioFormat::Json)]
+        {
+            let scenario =
+                Scenario::parse(content, format, &Environment::new(), &overrides).unwrap();
+            assert!(matches!(scenario.load_profile, Some(LoadProfile::Ramp { to: 5, .. })));
+            assert_eq!(scenario.http.unwrap().client, crate::ClientMode::Shared);
+        }
+
+        let overrides = ["load_profile.to=many".parse().unwrap()];
+        let err = Scenario::parse(JSON, ScenarioFormat::Json, &Environment::new(), &overrides)
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::JsonParse { line: None, .. }), "{err:?}");
+    }
+}
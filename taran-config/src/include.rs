@@ -0,0 +1,319 @@
+use crate::diagnostic::{SourceFile, SourceMap};
+use crate::error::{ConfigError, Result};
+use crate::format::ScenarioFormat;
+use serde_json::{Map, Value};
+use std::path::{Component, Path, PathBuf};
+
+/// Top-level keys an included file may contain
+const INCLUDABLE: [&str; 4] = ["include", "steps", "data", "defaults"];
+
+/// Merge the files listed in `include` into a parsed scenario document.
+///
+/// Included steps and data sources come before the document's own, in the
+/// order of the list. `[defaults]` are layered: a later include overrides an
+/// earlier one and the including file overrides them all. Included files
+/// may include others; paths are relative to the file that names them.
+///
+/// Every included file is added to `sources`, along with the file each
+/// merged step and data source came from.
+pub fn resolve_includes(
+    document: &mut Value,
+    path: Option<&Path>,
+    sources: &mut SourceMap,
+) -> Result<()> {
+    let Value::Object(document) = document else { return Ok(()) };
+    let base_dir = path.and_then(Path::parent).unwrap_or_else(|| Path::new(""));
+    let mut chain: Vec<(PathBuf, String)> = path
+        .map(|path| {
+            let name = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            (std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()), name)
+        })
+        .into_iter()
+        .collect();
+    let origins = expand(document, 0, base_dir, Path::new(""), &mut chain, sources)?;
+    if sources.files.len() > 1 {
+        (sources.steps, sources.data) = origins;
+    }
+    Ok(())
+}
+
+/// `(file, index)` of each step and each data source of a merged document
+type Origins = (Vec<(usize, usize)>, Vec<(usize, usize)>);
+
+/// Merge the includes of one document. `dir` is its directory relative to
+/// `base_dir`, the directory of the root scenario; `chain` holds the files
+/// being included, to detect cycles. `file` is the document's index in
+/// `sources`.
+fn expand(
+    document: &mut Map<String, Value>,
+    file: usize,
+    base_dir: &Path,
+    dir: &Path,
+    chain: &mut Vec<(PathBuf, String)>,
+    sources: &mut SourceMap,
+) -> Result<Origins> {
+    let own = |key: &str| {
+        let len = document.get(key).and_then(Value::as_array).map_or(0, Vec::len);
+        (0..len).map(|index| (file, index)).collect::<Vec<_>>()
+    };
+    let (mut step_origins, mut data_origins) = (own("steps"), own("data"));
+    let Some(includes) = document.get("include") else {
+        return Ok((step_origins, data_origins));
+    };
+    let includes: Vec<String> = serde_json::from_value(includes.clone()).map_err(|_| {
+        ConfigError::InvalidScenario("include must be a list of file paths".to_string())
+    })?;
+
+    let mut steps = Vec::new();
+    let mut data = Vec::new();
+    let mut defaults = Map::new();
+    let (mut included_steps, mut included_data) = (Vec::new(), Vec::new());
+    for include in includes {
+        let relative = normalize(&dir.join(&include));
+        let name = relative.display().to_string();
+        let invalid = |reason: String| ConfigError::Include { path: name.clone(), reason };
+
+        let path = base_dir.join(&relative);
+        let canonical = std::fs::canonicalize(&path).map_err(|e| invalid(e.to_string()))?;
+        if let Some(start) = chain.iter().position(|(included, _)| *included == canonical) {
+            let cycle: Vec<&str> = chain[start..].iter().map(|(_, name)| name.as_str()).collect();
+            return Err(invalid(format!("include cycle {} -> {name}", cycle.join(" -> "))));
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|e| invalid(e.to_string()))?;
+        let format = ScenarioFormat::from_path(&path).map_err(|e| invalid(e.to_string()))?;
+        let Value::Object(mut fragment) =
+            format.parse(&content).map_err(|e| invalid(e.to_string()))?
+        else {
+            return Err(invalid("expected a table of steps, data and defaults".to_string()));
+        };
+        if let Some(key) = fragment.keys().find(|key| !INCLUDABLE.contains(&key.as_str())) {
+            return Err(invalid(format!(
+                "'{key}' cannot be included, only {}",
+                INCLUDABLE.join(", ")
+            )));
+        }
+
+        let fragment_dir = relative.parent().unwrap_or(dir).to_path_buf();
+        let fragment_file = sources.files.len();
+        sources.files.push(SourceFile { path: Some(path), content, format });
+        chain.push((canonical, name.clone()));
+        let (steps_from, data_from) =
+            expand(&mut fragment, fragment_file, base_dir, &fragment_dir, chain, sources)?;
+        chain.pop();
+        included_steps.extend(steps_from);
+        included_data.extend(data_from);
+
+        steps.extend(take_array(&mut fragment, "steps").map_err(&invalid)?);
+        for mut source in take_array(&mut fragment, "data").map_err(&invalid)? {
+            // Data files are relative to the file that lists them
+            if let Some(Value::String(file)) = source.get_mut("file") {
+                if Path::new(file.as_str()).is_relative() {
+                    *file = fragment_dir.join(file.as_str()).display().to_string();
+                }
+            }
+            data.push(source);
+        }
+        match fragment.remove("defaults") {
+            Some(Value::Object(layer)) => overlay(&mut defaults, layer),
+            Some(_) => return Err(invalid("defaults must be a table".to_string())),
+            None => {}
+        }
+    }
+
+    // The document's own entries go last; leave anything malformed in place
+    // for deserialization to report
+    for (key, mut included) in [("steps", steps), ("data", data)] {
+        match document.get_mut(key) {
+            Some(Value::Array(own)) => {
+                included.append(own);
+                *own = included;
+            }
+            None if !included.is_empty() => {
+                document.insert(key.to_string(), Value::Array(included));
+            }
+            _ => {}
+        }
+    }
+    match document.remove("defaults") {
+        Some(Value::Object(own)) => overlay(&mut defaults, own),
+        Some(other) => {
+            document.insert("defaults".to_string(), other);
+            defaults.clear();
+        }
+        None => {}
+    }
+    if !defaults.is_empty() {
+        document.insert("defaults".to_string(), Value::Object(defaults));
+    }
+    included_steps.append(&mut step_origins);
+    included_data.append(&mut data_origins);
+    Ok((included_steps, included_data))
+}
+
+/// Resolve `.` and `..` without touching the file system, so paths read
+/// well in errors
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(normalized.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn take_array(
+    fragment: &mut Map<String, Value>,
+    key: &str,
+) -> std::result::Result<Vec<Value>, String> {
+    match fragment.remove(key) {
+        Some(Value::Array(items)) => Ok(items),
+        Some(_) => Err(format!("{key} must be a list")),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Copy `source` over `target`, merging nested tables key by key
+fn overlay(target: &mut Map<String, Value>, source: Map<String, Value>) {
+    for (key, value) in source {
+        match (target.get_mut(&key), value) {
+            (Some(Value::Object(existing)), Value::Object(value)) => overlay(existing, value),
+            (_, value) => {
+                target.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::Scenario;
+    use std::path::Path;
+
+    const SCENARIO: &str = r#"
+include = ["common/auth.toml", "common/catalog.yaml"]
+
+[scenario]
+name = "Checkout"
+
+[defaults]
+headers = { "X-Suite" = "checkout" }
+
+[load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+
+[[steps]]
+name = "Checkout"
+protocol = "http"
+method = "POST"
+url = "/checkout"
+"#;
+
+    const AUTH: &str = r#"
+include = ["base.json"]
+
+[defaults.headers]
+Accept = "application/json"
+"X-Suite" = "auth"
+
+[[data]]
+file = "users.csv"
+
+[[steps]]
+name = "Login"
+protocol = "http"
+method = "POST"
+url = "/login"
+body = "user={{username}}"
+"#;
+
+    const CATALOG: &str = "
+steps:
+  - name: Browse
+    protocol: http
+    method: GET
+    url: /products
+";
+
+    fn write(dir: &Path, files: &[(&str, &str)]) {
+        for (name, content) in files {
+            let path = dir.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_includes_are_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            &[
+                ("checkout.toml", SCENARIO),
+                ("common/auth.toml", AUTH),
+                ("common/catalog.yaml", CATALOG),
+                ("common/base.json", r#"{"defaults": {"base_url": "https://shop.example.com"}}"#),
+                ("common/users.csv", "username\nalice\n"),
+            ],
+        );
+
+        let scenario = Scenario::from_file(&dir.path().join("checkout.toml")).unwrap();
+        let names: Vec<&str> = scenario.steps.iter().map(|step| step.name.as_str()).collect();
+        assert_eq!(names, ["Login", "Browse", "Checkout"]);
+        assert_eq!(scenario.steps[0].url, "https://shop.example.com/login");
+        assert_eq!(scenario.steps[2].headers["X-Suite"], "checkout");
+        assert_eq!(scenario.steps[2].headers["Accept"], "application/json");
+        assert_eq!(scenario.data[0].file, Path::new("common/users.csv"));
+        scenario.validate().unwrap();
+
+        // Merged steps are located in the file that defines them
+        let location = scenario.sources.locate("steps[1].url").unwrap();
+        assert!(location.file.unwrap().ends_with("common/catalog.yaml"));
+        assert_eq!((location.line, location.text.trim()), (6, "url: /products"));
+        assert_eq!(scenario.sources.locate("steps[2].url").unwrap().line, 19);
+    }
+
+    #[test]
+    fn test_include_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            &[
+                ("main.toml", &SCENARIO.replace(", \"common/catalog.yaml\"", "")),
+                ("common/auth.toml", "include = [\"../shared/a.toml\"]"),
+                ("shared/a.toml", "include = [\"../common/auth.toml\"]"),
+            ],
+        );
+        let err = Scenario::from_file(&dir.path().join("main.toml")).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Invalid include common/auth.toml: include cycle common/auth.toml -> shared/a.toml \
+             -> common/auth.toml"
+        );
+
+        write(dir.path(), &[("common/auth.toml", "include = [\"../main.toml\"]")]);
+        let err = Scenario::from_file(&dir.path().join("main.toml")).unwrap_err().to_string();
+        assert!(err.ends_with("include cycle main.toml -> common/auth.toml -> main.toml"));
+
+        write(dir.path(), &[("common/auth.toml", "[load_profile]\ntype = \"constant\"")]);
+        let err = Scenario::from_file(&dir.path().join("main.toml")).unwrap_err().to_string();
+        assert!(err.contains("'load_profile' cannot be included"), "{err}");
+
+        std::fs::remove_file(dir.path().join("common/auth.toml")).unwrap();
+        let err = Scenario::from_file(&dir.path().join("main.toml")).unwrap_err().to_string();
+        assert!(err.starts_with("Invalid include common/auth.toml: "), "{err}");
+    }
+}
@@ -1,7 +1,31 @@
+pub mod data;
+pub mod defaults;
+pub mod diagnostic;
 pub mod duration;
+pub mod env;
 pub mod error;
+pub mod format;
+mod include;
+pub mod overrides;
 pub mod scenario;
+pub mod template;
+pub mod threshold;
+mod validate;
 
+pub use data::{DataFormat, DataSet, DataSource, DataStrategy, OnExhausted};
+pub use defaults::StepDefaults;
+pub use diagnostic::{Diagnostic, SourceLocation, SourceMap};
 pub use duration::HumanDuration;
+pub use env::{Environment, Secrets, MASK};
 pub use error::{ConfigError, Result};
-pub use scenario::{Assertions, Extractor, LoadProfile, Scenario, Step};
+pub use format::ScenarioFormat;
+pub use overrides::Override;
+pub use scenario::{
+    Assertions, ClientMode, Extractor, HttpSettings, LoadProfile, MetricsSettings, NamedScenario,
+    Scenario, Step, DEFAULT_SCENARIO_NAME, DEFAULT_SNAPSHOT_INTERVAL,
+};
+pub use template::{Builtin, Segment, Template};
+pub use threshold::{
+    Comparison, LatencyStat, Threshold, ThresholdMetric, ThresholdSettings, DEFAULT_ABORT_WINDOW,
+};
+pub use validate::HTTP_METHODS;
@@ -0,0 +1,265 @@
+use crate::duration::HumanDuration;
+use crate::error::{ConfigError, Result};
+use crate::scenario::LoadProfile;
+use std::str::FromStr;
+
+/// A `path.to.key=value` patch applied to the scenario document before it
+/// is parsed, e.g. `load_profile.users=50` or `steps.0.url=http://staging`.
+///
+/// Numeric path segments index arrays; missing tables are created. The
+/// value is read as a TOML value when it is one (`50`, `true`, `"text"`,
+/// `["a", "b"]`) and as a plain string otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Override {
+    pub path: Vec<String>,
+    pub value: serde_json::Value,
+}
+
+impl FromStr for Override {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| ConfigError::InvalidOverride {
+            field: s.to_string(),
+            reason: reason.to_string(),
+        };
+        let (path, raw) = s.split_once('=').ok_or_else(|| invalid("expected KEY=VALUE"))?;
+        let path: Vec<String> = path.trim().split('.').map(str::to_string).collect();
+        if path.iter().any(String::is_empty) {
+            return Err(invalid("empty key in path"));
+        }
+        let raw = raw.trim();
+        let value =
+            toml::from_str::<serde_json::Map<String, serde_json::Value>>(&format!("value = {raw}"))
+                .ok()
+                .and_then(|mut table| table.remove("value"))
+                .unwrap_or_else(|| serde_json::Value::String(raw.to_string()));
+        Ok(Self { path, value })
+    }
+}
+
+impl Override {
+    /// Set the value in a parsed scenario document
+    pub(crate) fn apply(&self, document: &mut serde_json::Value) -> Result<()> {
+        let invalid = |depth: usize, reason: &str| {
+            let parent =
+                if depth == 0 { "the document".to_string() } else { self.path[..depth].join(".") };
+            ConfigError::InvalidOverride {
+                field: self.path.join("."),
+                reason: format!("{parent} {reason}"),
+            }
+        };
+
+        let mut current = document;
+        for (depth, key) in self.path.iter().enumerate() {
+            let last = depth + 1 == self.path.len();
+            // An empty YAML key is null, treat it like an empty table
+            if current.is_null() {
+                *current = serde_json::Value::Object(serde_json::Map::new());
+            }
+            current = match current {
+                serde_json::Value::Object(table) => {
+                    if last {
+                        table.insert(key.clone(), self.value.clone());
+                        return Ok(());
+                    }
+                    table
+                        .entry(key.clone())
+                        .or_insert_with(|| serde_json::Value::Object(serde_json::Map::new()))
+                }
+                serde_json::Value::Array(array) => {
+                    let len = array.len();
+                    let slot =
+                        key.parse::<usize>().ok().and_then(|i| array.get_mut(i)).ok_or_else(
+                            || invalid(depth, &format!("has no entry {key} ({len} entries)")),
+                        )?;
+                    if last {
+                        *slot = self.value.clone();
+                        return Ok(());
+                    }
+                    slot
+                }
+                _ => return Err(invalid(depth, "is not a table or array")),
+            };
+        }
+        Ok(())
+    }
+}
+
+impl LoadProfile {
+    /// Replace the number of virtual users.
+    ///
+    /// Sets `users` of constant profiles, `to` of ramps, `peak` of spikes,
+    /// `max_vus` of arrival-rate profiles and `vus` of iteration profiles.
+    /// Stepped profiles set users per stage and cannot be overridden.
+    pub fn override_users(&mut self, users: usize) -> Result<()> {
+        let invalid = |reason: &str| ConfigError::InvalidOverride {
+            field: "users".to_string(),
+            reason: reason.to_string(),
+        };
+        if users == 0 {
+            return Err(invalid("must be greater than 0"));
+        }
+        match self {
+            Self::Constant { users: target, .. }
+            | Self::Ramp { to: target, .. }
+            | Self::Spike { peak: target, .. }
+            | Self::ConstantArrivalRate { max_vus: target, .. }
+            | Self::RampingArrivalRate { max_vus: target, .. }
+            | Self::PerVuIterations { vus: target, .. }
+            | Self::SharedIterations { vus: target, .. } => *target = users,
+            Self::Stepped { .. } => {
+                return Err(invalid(
+                    "a stepped load profile sets users per stage; \
+                     override load_profile.steps.N.users instead",
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace how long the load runs.
+    ///
+    /// Sets `duration`, `total_duration` of spikes and `max_duration` of
+    /// iteration profiles. Stepped profiles set a duration per stage and
+    /// cannot be overridden.
+    pub fn override_duration(&mut self, duration: HumanDuration) -> Result<()> {
+        let invalid =
+            |reason: String| ConfigError::InvalidOverride { field: "duration".to_string(), reason };
+        if duration.as_duration().is_zero() {
+            return Err(invalid("must be greater than 0".to_string()));
+        }
+        match self {
+            Self::Constant { duration: target, .. }
+            | Self::Ramp { duration: target, .. }
+            | Self::ConstantArrivalRate { duration: target, .. }
+            | Self::RampingArrivalRate { duration: target, .. } => *target = duration,
+            Self::Spike { spike_duration, total_duration, .. } => {
+                if spike_duration.as_duration() > duration.as_duration() {
+                    return Err(invalid(format!(
+                        "shorter than the spike itself ({})",
+                        String::from(*spike_duration)
+                    )));
+                }
+                *total_duration = duration;
+            }
+            Self::PerVuIterations { max_duration, .. }
+            | Self::SharedIterations { max_duration, .. } => *max_duration = Some(duration),
+            Self::Stepped { .. } => {
+                return Err(invalid(
+                    "a stepped load profile sets a duration per stage; \
+                     override load_profile.steps.N.duration instead"
+                        .to_string(),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::Scenario;
+    use std::time::Duration;
+
+    const SCENARIO: &str = r#"
+[scenario]
+name = "Overrides"
+
+[load_profile]
+type = "constant"
+users = 10
+duration = "1m"
+
+[[steps]]
+name = "Home"
+protocol = "http"
+method = "GET"
+url = "https://example.com/"
+"#;
+
+    fn load(overrides: &[&str]) -> crate::Result<Scenario> {
+        let overrides: Vec<Override> = overrides.iter().map(|o| o.parse().unwrap()).collect();
+        Scenario::from_toml_with_overrides(SCENARIO, &overrides)
+    }
+
+    #[test]
+    fn test_parse_override() {
+        let patch: Override = "load_profile.users=50".parse().unwrap();
+        assert_eq!(patch.path, ["load_profile", "users"]);
+        assert_eq!(patch.value, serde_json::json!(50));
+        let patch: Override = "steps.0.url = https://staging/".parse().unwrap();
+        assert_eq!(patch.value, serde_json::json!("https://staging/"));
+        let patch: Override = r#"scenario.name="a=b""#.parse().unwrap();
+        assert_eq!(patch.value, serde_json::json!("a=b"));
+
+        assert!("load_profile.users".parse::<Override>().is_err());
+        assert!("load_profile..users=1".parse::<Override>().is_err());
+    }
+
+    #[test]
+    fn test_set_patches_the_document() {
+        let scenario = load(&[
+            "load_profile.users=50",
+            "steps.0.url=https://staging.example.com/",
+            "http.client=shared",
+            "scenario.description=Soak",
+        ])
+        .unwrap();
+        let Some(LoadProfile::Constant { users, .. }) = scenario.load_profile else {
+            unreachable!()
+        };
+        assert_eq!(users, 50);
+        assert_eq!(scenario.steps[0].url, "https://staging.example.com/");
+        assert_eq!(scenario.http.unwrap().client, crate::ClientMode::Shared);
+        assert_eq!(scenario.scenario.description.as_deref(), Some("Soak"));
+
+        let err = load(&["steps.3.url=x"]).unwrap_err().to_string();
+        assert!(err.contains("steps has no entry 3 (1 entries)"), "{err}");
+        assert!(load(&["scenario.name.first=x"]).is_err());
+        // Values are still type-checked when the patched document is parsed
+        assert!(load(&["load_profile.users=many"]).is_err());
+    }
+
+    #[test]
+    fn test_override_load() {
+        let mut scenario = load(&[]).unwrap();
+        scenario.override_load(Some(3), Some("10s".to_string().try_into().unwrap())).unwrap();
+        let Some(LoadProfile::Constant { users, duration, .. }) = scenario.load_profile else {
+            unreachable!()
+        };
+        assert_eq!((users, duration.as_duration()), (3, Duration::from_secs(10)));
+
+        let profile = |toml: &str| -> LoadProfile { toml::from_str(toml).unwrap() };
+        let seconds = |s: u64| HumanDuration(Duration::from_secs(s));
+
+        let mut spike = profile(
+            "type = \"spike\"\nbaseline = 1\npeak = 5\nspike_duration = \"30s\"\ntotal_duration = \"2m\"",
+        );
+        spike.override_users(20).unwrap();
+        assert!(spike.override_duration(seconds(10)).is_err());
+        spike.override_duration(seconds(60)).unwrap();
+        assert!(
+            matches!(spike, LoadProfile::Spike { peak: 20, total_duration, .. } if total_duration == seconds(60))
+        );
+
+        let mut rate = profile(
+            "type = \"constant_arrival_rate\"\nrate = 5.0\nduration = \"1m\"\nmax_vus = 10",
+        );
+        rate.override_users(40).unwrap();
+        assert!(matches!(rate, LoadProfile::ConstantArrivalRate { max_vus: 40, .. }));
+
+        let mut iterations = profile("type = \"shared_iterations\"\nvus = 2\niterations = 100");
+        iterations.override_duration(seconds(30)).unwrap();
+        assert!(matches!(iterations, LoadProfile::SharedIterations { max_duration: Some(_), .. }));
+
+        let mut stepped =
+            profile("type = \"stepped\"\nsteps = [{ users = 1, duration = \"10s\" }]");
+        let err = stepped.override_users(5).unwrap_err().to_string();
+        assert!(err.starts_with("Cannot override users: a stepped load profile"), "{err}");
+        assert!(stepped.override_duration(seconds(5)).is_err());
+        assert!(rate.override_users(0).is_err());
+    }
+}
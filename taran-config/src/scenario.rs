@@ -1,26 +1,136 @@
+use crate::data::DataSource;
+use crate::defaults::StepDefaults;
+use crate::diagnostic::SourceMap;
 use crate::duration::HumanDuration;
+use crate::env::{Environment, Secrets};
 use crate::error::{ConfigError, Result};
+use crate::format::ScenarioFormat;
+use crate::include::resolve_includes;
+use crate::overrides::Override;
+use crate::threshold::ThresholdSettings;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// Name of the implicit scenario built from top-level `load_profile` and `steps`
+pub const DEFAULT_SCENARIO_NAME: &str = "default";
+
+/// Time-series window length used when `[metrics]` does not set one
+pub const DEFAULT_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(1);
 
 /// Root scenario configuration
+///
+/// A file either describes a single workload with top-level `load_profile`
+/// and `steps`, or several independent workloads in `[[scenarios]]`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Scenario {
+    /// Files whose steps, data and defaults were merged into this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
     pub scenario: ScenarioInfo,
-    pub load_profile: LoadProfile,
+    /// Settings every step inherits, already applied once loaded
+    #[serde(default)]
+    pub defaults: Option<StepDefaults>,
+    #[serde(default)]
+    pub load_profile: Option<LoadProfile>,
     #[serde(default)]
     pub steps: Vec<Step>,
+    #[serde(default)]
+    pub scenarios: Vec<NamedScenario>,
+    /// HTTP settings for every scenario that does not set its own
+    #[serde(default)]
+    pub http: Option<HttpSettings>,
+    /// Data files feeding rows into VU variables, shared by every scenario
+    #[serde(default)]
+    pub data: Vec<DataSource>,
+    #[serde(default)]
+    pub metrics: MetricsSettings,
+    #[serde(default)]
+    pub thresholds: ThresholdSettings,
+    /// Directory of the scenario file, used to resolve relative data paths
+    #[serde(skip)]
+    pub base_dir: Option<std::path::PathBuf>,
+    /// Values expanded from `${secret:NAME}` references
+    #[serde(skip)]
+    pub secrets: Secrets,
+    /// Files the scenario was read from, to locate diagnostics
+    #[serde(skip)]
+    pub sources: SourceMap,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ScenarioInfo {
     pub name: String,
     #[serde(default)]
     pub description: Option<String>,
 }
 
+/// A named workload with its own steps, load profile and start offset
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "lowercase")]
+#[serde(deny_unknown_fields)]
+pub struct NamedScenario {
+    pub name: String,
+    /// Delay between test start and this scenario's first iteration
+    #[serde(default)]
+    pub start_after: Option<HumanDuration>,
+    pub load_profile: LoadProfile,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+    #[serde(default)]
+    pub http: Option<HttpSettings>,
+}
+
+/// HTTP connection handling for a workload
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpSettings {
+    #[serde(default)]
+    pub client: ClientMode,
+}
+
+/// Which VUs share an HTTP client and its connection pool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientMode {
+    /// Each VU keeps its own client and connections, like a browser
+    #[default]
+    PerVu,
+    /// All VUs of the scenario share one client, for maximum throughput
+    Shared,
+    /// Every request opens a new connection
+    NoKeepAlive,
+}
+
+/// How metrics are sampled during the run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsSettings {
+    /// Length of each time-series window
+    #[serde(default)]
+    pub snapshot_interval: Option<HumanDuration>,
+    /// Expected time between requests of a closed-loop VU. Responses slower
+    /// than this back-fill the response-time histogram with the requests
+    /// that would have been sent meanwhile.
+    #[serde(default)]
+    pub expected_interval: Option<HumanDuration>,
+}
+
+impl MetricsSettings {
+    pub fn snapshot_interval(&self) -> Duration {
+        self.snapshot_interval.map_or(DEFAULT_SNAPSHOT_INTERVAL, |d| d.as_duration())
+    }
+}
+
+impl NamedScenario {
+    pub fn start_offset(&self) -> Duration {
+        self.start_after.map_or(Duration::ZERO, |d| d.as_duration())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
 pub enum LoadProfile {
     Constant {
         users: usize,
@@ -42,15 +152,48 @@ pub enum LoadProfile {
         spike_duration: HumanDuration,
         total_duration: HumanDuration,
     },
+    /// Open-loop: start `rate` iterations per second regardless of response times
+    #[serde(rename = "constant_arrival_rate")]
+    ConstantArrivalRate {
+        rate: f64,
+        duration: HumanDuration,
+        max_vus: usize,
+    },
+    /// Open-loop: iteration rate changes linearly from `from` to `to` per second
+    #[serde(rename = "ramping_arrival_rate")]
+    RampingArrivalRate {
+        from: f64,
+        to: f64,
+        duration: HumanDuration,
+        max_vus: usize,
+    },
+    /// Each of `vus` VUs runs exactly `iterations` iterations
+    #[serde(rename = "per_vu_iterations")]
+    PerVuIterations {
+        vus: usize,
+        iterations: u64,
+        #[serde(default)]
+        max_duration: Option<HumanDuration>,
+    },
+    /// `vus` VUs share a pool of `iterations` iterations
+    #[serde(rename = "shared_iterations")]
+    SharedIterations {
+        vus: usize,
+        iterations: u64,
+        #[serde(default)]
+        max_duration: Option<HumanDuration>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct LoadStep {
     pub users: usize,
     pub duration: HumanDuration,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Step {
     pub name: String,
     pub protocol: String,
@@ -68,9 +211,14 @@ pub struct Step {
 
     #[serde(default)]
     pub extract: Option<HashMap<String, Extractor>>,
+
+    /// Request timeout, instead of the client's 30 seconds
+    #[serde(default)]
+    pub timeout: Option<HumanDuration>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Assertions {
     #[serde(default)]
     pub status: Option<u16>,
@@ -83,49 +231,176 @@ pub struct Assertions {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Extractor {
-    pub from: String, // "body", "header", "status"
+    pub from: String, // "body", "header", "status", "cookie"
     #[serde(rename = "type")]
-    pub extractor_type: String, // "jsonpath", "regex", "xpath"
+    pub extractor_type: String, // "jsonpath", "regex", "xpath", "header", "status", "cookie"
+    #[serde(default)]
     pub expr: String,
+    /// Fail the step when nothing can be extracted
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+const fn default_required() -> bool {
+    true
+}
+
+impl Step {
+    /// Every field that may contain `{{...}}` placeholders, as
+    /// `(field path, raw value)` pairs
+    pub fn templated_fields(&self) -> Vec<(String, &str)> {
+        let mut fields = vec![("url".to_string(), self.url.as_str())];
+        let mut headers: Vec<_> = self.headers.iter().collect();
+        headers.sort();
+        for (name, value) in headers {
+            fields.push((format!("headers.{name}"), value.as_str()));
+        }
+        if let Some(body) = &self.body {
+            fields.push(("body".to_string(), body.as_str()));
+        }
+        fields
+    }
 }
 
 impl Scenario {
     /// Load scenario from TOML content
     pub fn from_toml(content: &str) -> Result<Self> {
-        let content = toml::from_str(content).map_err(ConfigError::TomlParse)?;
-        Ok(content)
+        Self::parse(content, ScenarioFormat::Toml, &Environment::new(), &[])
+    }
+
+    /// Load scenario from YAML content
+    pub fn from_yaml(content: &str) -> Result<Self> {
+        Self::parse(content, ScenarioFormat::Yaml, &Environment::new(), &[])
+    }
+
+    /// Load scenario from JSON content
+    pub fn from_json(content: &str) -> Result<Self> {
+        Self::parse(content, ScenarioFormat::Json, &Environment::new(), &[])
     }
 
-    /// Load scenario from TOML file
+    /// Load scenario from TOML content, patching it with `overrides` first
+    pub fn from_toml_with_overrides(content: &str, overrides: &[Override]) -> Result<Self> {
+        Self::parse(content, ScenarioFormat::Toml, &Environment::new(), overrides)
+    }
+
+    /// Load scenario from content in any format, with includes relative to
+    /// the current directory.
+    ///
+    /// Includes are merged and `overrides` applied first, then `${NAME}`
+    /// references are expanded from `env`, so override values may use them
+    /// too. Steps inherit `[defaults]` last.
+    pub fn parse(
+        content: &str,
+        format: ScenarioFormat,
+        env: &Environment,
+        overrides: &[Override],
+    ) -> Result<Self> {
+        Self::parse_at(content, format, None, env, overrides)
+    }
+
+    /// Load scenario from a TOML, YAML or JSON file, by extension
     pub fn from_file(path: &std::path::Path) -> Result<Self> {
+        Self::load(path, &Environment::new(), &[])
+    }
+
+    /// Load scenario from a TOML, YAML or JSON file, see [`Self::parse`]
+    pub fn load(path: &std::path::Path, env: &Environment, overrides: &[Override]) -> Result<Self> {
+        let format = ScenarioFormat::from_path(path)?;
         let content = std::fs::read_to_string(path).map_err(ConfigError::FileRead)?;
-        Self::from_toml(&content)
+        let mut scenario = Self::parse_at(&content, format, Some(path), env, overrides)?;
+        scenario.base_dir = path.parent().map(std::path::Path::to_path_buf);
+        Ok(scenario)
     }
 
-    /// Validate the scenario configuration
-    pub fn validate(&self) -> Result<()> {
-        if self.scenario.name.is_empty() {
-            return Err(ConfigError::MissingField("scenario.name".to_string()));
+    fn parse_at(
+        content: &str,
+        format: ScenarioFormat,
+        path: Option<&std::path::Path>,
+        env: &Environment,
+        overrides: &[Override],
+    ) -> Result<Self> {
+        let mut sources = SourceMap::new(path.map(std::path::Path::to_path_buf), content, format);
+        // Straight from the source when possible, to keep error positions
+        if overrides.is_empty() && !content.contains("${") {
+            let mut scenario: Self = format.parse(content)?;
+            if scenario.include.is_empty() {
+                scenario.sources = sources;
+                scenario.apply_defaults();
+                return Ok(scenario);
+            }
+        }
+        let mut document: serde_json::Value = format.parse(content)?;
+        resolve_includes(&mut document, path, &mut sources)?;
+        for patch in overrides {
+            patch.apply(&mut document)?;
         }
+        let mut secrets = Secrets::default();
+        env.interpolate(&mut document, &mut secrets)?;
+        let mut scenario: Self = format.deserialize_document(document)?;
+        scenario.secrets = secrets;
+        scenario.sources = sources;
+        scenario.apply_defaults();
+        Ok(scenario)
+    }
 
-        if self.steps.is_empty() {
-            return Err(ConfigError::InvalidScenario(
-                "Scenario must have at least one step".to_string(),
-            ));
+    fn apply_defaults(&mut self) {
+        let Some(defaults) = &self.defaults else { return };
+        let named = self.scenarios.iter_mut().flat_map(|named| named.steps.iter_mut());
+        for step in self.steps.iter_mut().chain(named) {
+            defaults.apply(step);
         }
+    }
 
-        for (i, step) in self.steps.iter().enumerate() {
-            if step.name.is_empty() {
-                return Err(ConfigError::MissingField(format!("steps[{i}].name")));
+    /// Replace the user count and duration of every load profile, see
+    /// [`LoadProfile::override_users`] and [`LoadProfile::override_duration`]
+    pub fn override_load(
+        &mut self,
+        users: Option<usize>,
+        duration: Option<HumanDuration>,
+    ) -> Result<()> {
+        let profiles = self
+            .load_profile
+            .iter_mut()
+            .chain(self.scenarios.iter_mut().map(|named| &mut named.load_profile));
+        for profile in profiles {
+            if let Some(users) = users {
+                profile.override_users(users)?;
             }
-            if step.url.is_empty() {
-                return Err(ConfigError::MissingField(format!("steps[{i}].url")));
+            if let Some(duration) = duration {
+                profile.override_duration(duration)?;
             }
         }
-
         Ok(())
     }
+
+    /// All workloads in the file.
+    ///
+    /// A single-workload file yields one scenario named
+    /// [`DEFAULT_SCENARIO_NAME`] built from the top-level `load_profile`
+    /// and `steps`. Scenarios without `[http]` settings inherit the
+    /// top-level ones.
+    pub fn named_scenarios(&self) -> Vec<NamedScenario> {
+        if !self.scenarios.is_empty() {
+            return self
+                .scenarios
+                .iter()
+                .map(|named| NamedScenario { http: named.http.or(self.http), ..named.clone() })
+                .collect();
+        }
+        self.load_profile
+            .as_ref()
+            .map(|load_profile| NamedScenario {
+                name: DEFAULT_SCENARIO_NAME.to_string(),
+                start_after: None,
+                load_profile: load_profile.clone(),
+                steps: self.steps.clone(),
+                http: self.http,
+            })
+            .into_iter()
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -154,4 +429,331 @@ url = "https://example.com/"
         assert_eq!(scenario.scenario.name, "Basic HTTP Test");
         assert_eq!(scenario.steps.len(), 1);
     }
+
+    #[test]
+    fn test_parse_arrival_rate_profile() {
+        let toml = r#"
+[scenario]
+name = "Open Loop"
+
+[load_profile]
+type = "constant_arrival_rate"
+rate = 50
+duration = "30s"
+max_vus = 20
+
+[[steps]]
+name = "GET Homepage"
+protocol = "http"
+method = "GET"
+url = "https://example.com/"
+"#;
+        let scenario = Scenario::from_toml(toml).unwrap();
+        assert!(matches!(
+            scenario.load_profile,
+            Some(LoadProfile::ConstantArrivalRate { max_vus: 20, .. })
+        ));
+        assert!(scenario.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_iterations_profile() {
+        let toml = r#"
+[scenario]
+name = "Seed Data"
+
+[load_profile]
+type = "shared_iterations"
+vus = 4
+iterations = 1000
+max_duration = "10m"
+
+[[steps]]
+name = "POST Item"
+protocol = "http"
+method = "POST"
+url = "https://example.com/items"
+"#;
+        let scenario = Scenario::from_toml(toml).unwrap();
+        assert!(matches!(
+            scenario.load_profile,
+            Some(LoadProfile::SharedIterations { vus: 4, iterations: 1000, max_duration: Some(_) })
+        ));
+        assert!(scenario.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_named_scenarios() {
+        let toml = r#"
+[scenario]
+name = "Shop"
+
+[[scenarios]]
+name = "browsing"
+
+[scenarios.load_profile]
+type = "constant"
+users = 50
+duration = "10m"
+
+[[scenarios.steps]]
+name = "GET Catalog"
+protocol = "http"
+method = "GET"
+url = "https://example.com/catalog"
+
+[[scenarios]]
+name = "checkout_spike"
+start_after = "5m"
+
+[scenarios.load_profile]
+type = "constant"
+users = 200
+duration = "1m"
+
+[[scenarios.steps]]
+name = "POST Checkout"
+protocol = "http"
+method = "POST"
+url = "https://example.com/checkout"
+"#;
+        let scenario = Scenario::from_toml(toml).unwrap();
+        assert!(scenario.validate().is_ok());
+
+        let named = scenario.named_scenarios();
+        assert_eq!(named.len(), 2);
+        assert_eq!(named[0].name, "browsing");
+        assert_eq!(named[0].start_offset(), std::time::Duration::ZERO);
+        assert_eq!(named[1].name, "checkout_spike");
+        assert_eq!(named[1].start_offset(), std::time::Duration::from_secs(300));
+    }
+
+    #[test]
+    fn test_http_client_mode_is_inherited() {
+        let toml = r#"
+[scenario]
+name = "Clients"
+
+[http]
+client = "shared"
+
+[[scenarios]]
+name = "api"
+load_profile = { type = "constant", users = 1, duration = "1s" }
+steps = [{ name = "a", protocol = "http", method = "GET", url = "https://example.com/" }]
+
+[[scenarios]]
+name = "fresh_connections"
+http = { client = "no_keep_alive" }
+load_profile = { type = "constant", users = 1, duration = "1s" }
+steps = [{ name = "b", protocol = "http", method = "GET", url = "https://example.com/" }]
+"#;
+        let named = Scenario::from_toml(toml).unwrap().named_scenarios();
+        assert_eq!(named[0].http.unwrap().client, ClientMode::Shared);
+        assert_eq!(named[1].http.unwrap().client, ClientMode::NoKeepAlive);
+
+        let toml = toml.replace("client = \"shared\"", "");
+        let named = Scenario::from_toml(&toml).unwrap().named_scenarios();
+        assert_eq!(named[0].http.unwrap_or_default().client, ClientMode::PerVu);
+    }
+
+    #[test]
+    fn test_metrics_snapshot_interval() {
+        let toml = r#"
+[scenario]
+name = "Sampled"
+
+[metrics]
+snapshot_interval = "5s"
+
+[load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+
+[[steps]]
+name = "GET"
+protocol = "http"
+method = "GET"
+url = "https://example.com/"
+"#;
+        let scenario = Scenario::from_toml(toml).unwrap();
+        assert_eq!(scenario.metrics.snapshot_interval(), Duration::from_secs(5));
+
+        let default = Scenario::from_toml(&toml.replace("snapshot_interval = \"5s\"", "")).unwrap();
+        assert_eq!(default.metrics.snapshot_interval(), DEFAULT_SNAPSHOT_INTERVAL);
+
+        let zero = Scenario::from_toml(&toml.replace("\"5s\"", "\"0s\"")).unwrap();
+        assert!(zero.validate().is_err());
+    }
+
+    #[test]
+    fn test_thresholds_section() {
+        let toml = r#"
+[scenario]
+name = "Gated"
+
+[thresholds]
+rules = ["http_req_duration.p95 < 500ms", "error_rate < 1%", '"Login".p99 < 1s']
+
+[load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+
+[[steps]]
+name = "Login"
+protocol = "http"
+method = "POST"
+url = "https://example.com/login"
+"#;
+        let scenario = Scenario::from_toml(toml).unwrap();
+        assert_eq!(scenario.thresholds.rules.len(), 3);
+        assert!(scenario.validate().is_ok());
+
+        let unknown =
+            Scenario::from_toml(&toml.replace("\"Login\".p99", "\"Logout\".p99")).unwrap();
+        let err = unknown.validate().unwrap_err().to_string();
+        assert!(err.contains("no step named 'Logout'"), "{err}");
+
+        let invalid = Scenario::from_toml(&toml.replace("< 500ms", "<< 500ms")).unwrap_err();
+        assert!(invalid.to_string().contains("Invalid threshold"), "{invalid}");
+    }
+
+    #[test]
+    fn test_single_workload_is_default_scenario() {
+        let toml = r#"
+[scenario]
+name = "Single"
+
+[load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+
+[[steps]]
+name = "GET"
+protocol = "http"
+method = "GET"
+url = "https://example.com/"
+"#;
+        let named = Scenario::from_toml(toml).unwrap().named_scenarios();
+        assert_eq!(named.len(), 1);
+        assert_eq!(named[0].name, DEFAULT_SCENARIO_NAME);
+        assert_eq!(named[0].steps.len(), 1);
+    }
+
+    #[test]
+    fn test_validate_rejects_duplicate_scenario_names() {
+        let toml = r#"
+[scenario]
+name = "Dup"
+
+[[scenarios]]
+name = "a"
+[scenarios.load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+[[scenarios.steps]]
+name = "GET"
+protocol = "http"
+method = "GET"
+url = "https://example.com/"
+
+[[scenarios]]
+name = "a"
+[scenarios.load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+[[scenarios.steps]]
+name = "GET"
+protocol = "http"
+method = "GET"
+url = "https://example.com/"
+"#;
+        let scenario = Scenario::from_toml(toml).unwrap();
+        assert!(matches!(scenario.validate(), Err(ConfigError::InvalidScenario(_))));
+    }
+
+    #[test]
+    fn test_validate_template_variables() {
+        let toml = r#"
+[scenario]
+name = "Templates"
+
+[load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+
+[[steps]]
+name = "Login"
+protocol = "http"
+method = "POST"
+url = "https://example.com/login?vu={{__vu}}"
+
+[steps.extract]
+token = { from = "body", type = "jsonpath", expr = "$.token" }
+
+[[steps]]
+name = "Profile"
+protocol = "http"
+method = "GET"
+url = "https://example.com/profile"
+
+[steps.headers]
+Authorization = "Bearer {{token}}"
+"#;
+        let scenario = Scenario::from_toml(toml).unwrap();
+        assert!(scenario.validate().is_ok());
+
+        let undefined = toml.replace("Bearer {{token}}", "Bearer {{session}}");
+        let scenario = Scenario::from_toml(&undefined).unwrap();
+        let err = scenario.validate().unwrap_err().to_string();
+        assert!(err.contains("steps[1].headers.Authorization"), "{err}");
+        assert!(err.contains("'session'"), "{err}");
+
+        let malformed = toml.replace("{{__vu}}", "{{__vu");
+        let scenario = Scenario::from_toml(&malformed).unwrap();
+        assert!(matches!(scenario.validate(), Err(ConfigError::InvalidTemplate(_))));
+    }
+
+    #[test]
+    fn test_data_columns_are_template_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("users.csv"), "username,password\nalice,secret\n").unwrap();
+        let toml = r#"
+[scenario]
+name = "Login"
+
+[load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+
+[[data]]
+file = "users.csv"
+strategy = "unique_per_vu"
+on_exhausted = "stop_test"
+
+[[steps]]
+name = "Login"
+protocol = "http"
+method = "POST"
+url = "https://example.com/login"
+body = "user={{username}}&pass={{password}}"
+"#;
+        let path = dir.path().join("login.toml");
+        std::fs::write(&path, toml).unwrap();
+        let scenario = Scenario::from_file(&path).unwrap();
+        assert_eq!(scenario.data[0].strategy, crate::DataStrategy::UniquePerVu);
+        assert_eq!(scenario.data[0].on_exhausted, crate::OnExhausted::StopTest);
+        assert!(scenario.validate().is_ok());
+
+        std::fs::write(&path, toml.replace("users.csv", "missing.csv")).unwrap();
+        let scenario = Scenario::from_file(&path).unwrap();
+        assert!(matches!(scenario.validate(), Err(ConfigError::DataFile { .. })));
+    }
 }
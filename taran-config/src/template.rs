@@ -0,0 +1,162 @@
+use crate::error::{ConfigError, Result};
+
+/// A string with `{{name}}` placeholders, parsed once at scenario load.
+///
+/// Placeholders name either a VU variable (set by extractors or data
+/// feeders) or a built-in: `__vu`, `__iter`, `__uuid`, `__timestamp` and
+/// `__random_int(min,max)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Literal(String),
+    Variable(String),
+    Builtin(Builtin),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Builtin {
+    /// Id of the executing VU
+    Vu,
+    /// Current iteration of the executing VU
+    Iteration,
+    /// Fresh random UUID v4
+    Uuid,
+    /// Milliseconds since the Unix epoch
+    Timestamp,
+    /// Uniform random integer in `min..=max`
+    RandomInt(i64, i64),
+}
+
+impl Template {
+    /// Parse a template string
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut rest = source;
+
+        while let Some(open) = rest.find("{{") {
+            if open > 0 {
+                segments.push(Segment::Literal(rest[..open].to_string()));
+            }
+            let after_open = &rest[open + 2..];
+            let close = after_open.find("}}").ok_or_else(|| {
+                ConfigError::InvalidTemplate(format!("unclosed '{{{{' in '{source}'"))
+            })?;
+            segments.push(parse_placeholder(after_open[..close].trim(), source)?);
+            rest = &after_open[close + 2..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+
+        Ok(Self { segments })
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// True when the template has no placeholders
+    pub fn is_literal(&self) -> bool {
+        self.segments.iter().all(|s| matches!(s, Segment::Literal(_)))
+    }
+
+    /// Names of the VU variables referenced by the template
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|s| match s {
+            Segment::Variable(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+}
+
+fn parse_placeholder(expr: &str, source: &str) -> Result<Segment> {
+    if expr.is_empty() {
+        return Err(ConfigError::InvalidTemplate(format!("empty placeholder in '{source}'")));
+    }
+
+    let builtin = match expr {
+        "__vu" => Builtin::Vu,
+        "__iter" => Builtin::Iteration,
+        "__uuid" => Builtin::Uuid,
+        "__timestamp" => Builtin::Timestamp,
+        _ if expr.starts_with("__random_int") => parse_random_int(expr)?,
+        _ if expr.starts_with("__") => {
+            return Err(ConfigError::InvalidTemplate(format!("unknown built-in '{expr}'")));
+        }
+        _ => {
+            if !expr.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '-') {
+                return Err(ConfigError::InvalidTemplate(format!(
+                    "invalid variable name '{expr}' in '{source}'"
+                )));
+            }
+            return Ok(Segment::Variable(expr.to_string()));
+        }
+    };
+    Ok(Segment::Builtin(builtin))
+}
+
+fn parse_random_int(expr: &str) -> Result<Builtin> {
+    let invalid =
+        || ConfigError::InvalidTemplate(format!("expected '__random_int(min,max)', got '{expr}'"));
+    let args = expr
+        .strip_prefix("__random_int")
+        .map(str::trim)
+        .and_then(|a| a.strip_prefix('('))
+        .and_then(|a| a.strip_suffix(')'))
+        .ok_or_else(invalid)?;
+    let (min, max) = args.split_once(',').ok_or_else(invalid)?;
+    let min: i64 = min.trim().parse().map_err(|_| invalid())?;
+    let max: i64 = max.trim().parse().map_err(|_| invalid())?;
+    if min > max {
+        return Err(ConfigError::InvalidTemplate(format!(
+            "'{expr}': min must not be greater than max"
+        )));
+    }
+    Ok(Builtin::RandomInt(min, max))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_literal() {
+        let template = Template::parse("https://example.com/").unwrap();
+        assert!(template.is_literal());
+        assert_eq!(template.segments(), [Segment::Literal("https://example.com/".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_variables_and_builtins() {
+        let template =
+            Template::parse("/users/{{ user_id }}/items?n={{__random_int(1, 100)}}&vu={{__vu}}")
+                .unwrap();
+        assert_eq!(
+            template.segments(),
+            [
+                Segment::Literal("/users/".to_string()),
+                Segment::Variable("user_id".to_string()),
+                Segment::Literal("/items?n=".to_string()),
+                Segment::Builtin(Builtin::RandomInt(1, 100)),
+                Segment::Literal("&vu=".to_string()),
+                Segment::Builtin(Builtin::Vu),
+            ]
+        );
+        assert_eq!(template.variables().collect::<Vec<_>>(), ["user_id"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{{token").is_err());
+        assert!(Template::parse("{{}}").is_err());
+        assert!(Template::parse("{{__unknown}}").is_err());
+        assert!(Template::parse("{{__random_int(10,1)}}").is_err());
+        assert!(Template::parse("{{__random_int(a,b)}}").is_err());
+        assert!(Template::parse("{{bad name}}").is_err());
+    }
+}
@@ -0,0 +1,375 @@
+use crate::duration::HumanDuration;
+use crate::error::{ConfigError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Span of recent metrics `abort_on_fail` rules are checked against when
+/// `[thresholds]` does not set one
+pub const DEFAULT_ABORT_WINDOW: Duration = Duration::from_secs(30);
+
+/// Pass/fail rules evaluated against the run's metrics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ThresholdSettings {
+    #[serde(default)]
+    pub rules: Vec<Threshold>,
+    /// Rolling span of metrics `abort_on_fail` rules are checked against
+    /// during the run
+    #[serde(default)]
+    pub abort_window: Option<HumanDuration>,
+}
+
+impl ThresholdSettings {
+    pub fn abort_window(&self) -> Duration {
+        self.abort_window.map_or(DEFAULT_ABORT_WINDOW, |d| d.as_duration())
+    }
+}
+
+/// One threshold expression, e.g. `http_req_duration.p95 < 500ms`,
+/// `error_rate < 1%`, `"Login".p99 < 1s` or `rps > 200`.
+///
+/// An optional quoted step name (or `"scenario/step"`) scopes the rule to
+/// that step; a bare statistic such as `p99` is short for
+/// `http_req_duration.p99`. Durations are normalised to milliseconds and
+/// rates to percent.
+///
+/// Written either as a plain string or as a table
+/// `{ threshold = "...", abort_on_fail = true, delay_abort_eval = "30s" }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "ThresholdSpec", into = "ThresholdSpec")]
+pub struct Threshold {
+    /// The expression as written
+    pub expression: String,
+    /// Step the rule applies to; `None` for the whole run
+    pub step: Option<String>,
+    pub metric: ThresholdMetric,
+    pub comparison: Comparison,
+    pub limit: f64,
+    /// Also check the rule during the run and stop the test once it fails
+    pub abort_on_fail: bool,
+    /// Grace period after the start before `abort_on_fail` is checked
+    pub delay_abort_eval: Option<HumanDuration>,
+}
+
+/// How a threshold is written in the scenario file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ThresholdSpec {
+    Expression(String),
+    Table {
+        threshold: String,
+        #[serde(default)]
+        abort_on_fail: bool,
+        #[serde(default)]
+        delay_abort_eval: Option<HumanDuration>,
+    },
+}
+
+/// Metric a threshold compares
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdMetric {
+    /// Response time (scheduled start to completion), `http_req_duration`
+    ResponseTime(LatencyStat),
+    /// Service time (actual start to completion), `service_time`
+    ServiceTime(LatencyStat),
+    /// Percentage of failed requests, `error_rate`
+    ErrorRate,
+    /// Requests per second, `rps`
+    Rps,
+    /// Number of requests, `requests`
+    Requests,
+}
+
+/// Statistic of a latency distribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencyStat {
+    Avg,
+    Min,
+    Max,
+    P50,
+    P75,
+    P90,
+    P95,
+    P99,
+    P999,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl Threshold {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let invalid = |reason: &str| ConfigError::InvalidThreshold {
+            expression: expression.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let (step, rest) = match expression.trim().strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').ok_or_else(|| invalid("unterminated step name"))?;
+                let rest = quoted[end + 1..]
+                    .strip_prefix('.')
+                    .ok_or_else(|| invalid("expected '.' after the step name"))?;
+                (Some(quoted[..end].to_string()), rest)
+            }
+            None => (None, expression.trim()),
+        };
+
+        let (position, comparison, operator) =
+            find_operator(rest).ok_or_else(|| invalid("expected one of <, <=, >, >=, =="))?;
+        let metric = parse_metric(rest[..position].trim()).map_err(|reason| invalid(&reason))?;
+        let limit = parse_limit(metric, rest[position + operator.len()..].trim())
+            .map_err(|reason| invalid(&reason))?;
+
+        Ok(Self {
+            expression: expression.to_string(),
+            step,
+            metric,
+            comparison,
+            limit,
+            abort_on_fail: false,
+            delay_abort_eval: None,
+        })
+    }
+
+    /// Grace period before the rule may abort the run
+    pub fn abort_delay(&self) -> Duration {
+        self.delay_abort_eval.map_or(Duration::ZERO, |d| d.as_duration())
+    }
+}
+
+impl TryFrom<ThresholdSpec> for Threshold {
+    type Error = ConfigError;
+
+    fn try_from(spec: ThresholdSpec) -> Result<Self> {
+        match spec {
+            ThresholdSpec::Expression(expression) => Self::parse(&expression),
+            ThresholdSpec::Table { threshold, abort_on_fail, delay_abort_eval } => {
+                if delay_abort_eval.is_some() && !abort_on_fail {
+                    return Err(ConfigError::InvalidThreshold {
+                        expression: threshold,
+                        reason: "delay_abort_eval requires abort_on_fail = true".to_string(),
+                    });
+                }
+                Ok(Self { abort_on_fail, delay_abort_eval, ..Self::parse(&threshold)? })
+            }
+        }
+    }
+}
+
+impl From<Threshold> for ThresholdSpec {
+    fn from(threshold: Threshold) -> Self {
+        if threshold.abort_on_fail {
+            Self::Table {
+                threshold: threshold.expression,
+                abort_on_fail: true,
+                delay_abort_eval: threshold.delay_abort_eval,
+            }
+        } else {
+            Self::Expression(threshold.expression)
+        }
+    }
+}
+
+impl ThresholdMetric {
+    /// Unit of the metric's values and limits
+    pub const fn unit(self) -> &'static str {
+        match self {
+            Self::ResponseTime(_) | Self::ServiceTime(_) => "ms",
+            Self::ErrorRate => "%",
+            Self::Rps => "req/s",
+            Self::Requests => "",
+        }
+    }
+}
+
+impl Comparison {
+    /// Whether `actual` satisfies the comparison against `limit`
+    pub fn holds(self, actual: f64, limit: f64) -> bool {
+        match self {
+            Self::Less => actual < limit,
+            Self::LessOrEqual => actual <= limit,
+            Self::Greater => actual > limit,
+            Self::GreaterOrEqual => actual >= limit,
+            Self::Equal => (actual - limit).abs() < f64::EPSILON,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+            Self::Equal => "==",
+        })
+    }
+}
+
+/// Position, comparison and text of the first operator in `s`
+fn find_operator(s: &str) -> Option<(usize, Comparison, &'static str)> {
+    let position = s.find(['<', '>', '='])?;
+    let operators = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("==", Comparison::Equal),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+    operators
+        .into_iter()
+        .find(|(operator, _)| s[position..].starts_with(operator))
+        .map(|(operator, comparison)| (position, comparison, operator))
+}
+
+fn parse_metric(path: &str) -> std::result::Result<ThresholdMetric, String> {
+    let (name, stat) = path.split_once('.').map_or((path, None), |(name, stat)| (name, Some(stat)));
+    match (name, stat) {
+        ("http_req_duration" | "response_time", Some(stat)) => {
+            Ok(ThresholdMetric::ResponseTime(parse_stat(stat)?))
+        }
+        ("service_time", Some(stat)) => Ok(ThresholdMetric::ServiceTime(parse_stat(stat)?)),
+        ("error_rate", None) => Ok(ThresholdMetric::ErrorRate),
+        ("rps", None) => Ok(ThresholdMetric::Rps),
+        ("requests", None) => Ok(ThresholdMetric::Requests),
+        ("http_req_duration" | "response_time" | "service_time", None) => {
+            Err(format!("'{name}' needs a statistic, e.g. '{name}.p95'"))
+        }
+        _ => parse_stat(path).map(ThresholdMetric::ResponseTime).map_err(|_| {
+            format!(
+                "unknown metric '{path}' (expected http_req_duration.<stat>, \
+                 service_time.<stat>, error_rate, rps, requests or a bare <stat>)"
+            )
+        }),
+    }
+}
+
+fn parse_stat(stat: &str) -> std::result::Result<LatencyStat, String> {
+    Ok(match stat {
+        "avg" => LatencyStat::Avg,
+        "min" => LatencyStat::Min,
+        "max" => LatencyStat::Max,
+        "med" | "p50" => LatencyStat::P50,
+        "p75" => LatencyStat::P75,
+        "p90" => LatencyStat::P90,
+        "p95" => LatencyStat::P95,
+        "p99" => LatencyStat::P99,
+        "p99.9" | "p999" => LatencyStat::P999,
+        _ => {
+            return Err(format!(
+                "unknown statistic '{stat}' (expected avg, min, max, med, p50, p75, p90, p95, \
+                 p99 or p99.9)"
+            ))
+        }
+    })
+}
+
+/// Parse the right-hand side, normalised to the metric's unit
+fn parse_limit(metric: ThresholdMetric, value: &str) -> std::result::Result<f64, String> {
+    let number = |s: &str| s.trim().parse::<f64>().map_err(|_| format!("invalid number '{s}'"));
+    match metric {
+        ThresholdMetric::ResponseTime(_) | ThresholdMetric::ServiceTime(_) => {
+            // A bare number is milliseconds
+            number(value).or_else(|_| {
+                HumanDuration::try_from(value.to_string())
+                    .map(|d| d.as_duration().as_secs_f64() * 1000.0)
+            })
+        }
+        ThresholdMetric::ErrorRate => number(value.strip_suffix('%').unwrap_or(value)),
+        ThresholdMetric::Rps => number(value.strip_suffix("/s").unwrap_or(value)),
+        ThresholdMetric::Requests => number(value),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_thresholds() {
+        let p95 = Threshold::parse("http_req_duration.p95 < 500ms").unwrap();
+        assert_eq!(p95.step, None);
+        assert_eq!(p95.metric, ThresholdMetric::ResponseTime(LatencyStat::P95));
+        assert_eq!(p95.comparison, Comparison::Less);
+        assert!((p95.limit - 500.0).abs() < f64::EPSILON);
+
+        let errors = Threshold::parse("error_rate <= 1.5%").unwrap();
+        assert_eq!(
+            (errors.metric, errors.comparison),
+            (ThresholdMetric::ErrorRate, Comparison::LessOrEqual)
+        );
+        assert!((errors.limit - 1.5).abs() < f64::EPSILON);
+
+        let login = Threshold::parse("\"Login\".p99 < 1s").unwrap();
+        assert_eq!(login.step.as_deref(), Some("Login"));
+        assert_eq!(login.metric, ThresholdMetric::ResponseTime(LatencyStat::P99));
+        assert!((login.limit - 1000.0).abs() < f64::EPSILON);
+
+        let rps = Threshold::parse("rps > 200").unwrap();
+        assert_eq!((rps.metric, rps.comparison), (ThresholdMetric::Rps, Comparison::Greater));
+
+        let service = Threshold::parse("\"shop/Buy\".service_time.p99.9 >= 20").unwrap();
+        assert_eq!(service.step.as_deref(), Some("shop/Buy"));
+        assert_eq!(service.metric, ThresholdMetric::ServiceTime(LatencyStat::P999));
+        assert!((service.limit - 20.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_invalid_thresholds() {
+        for (expression, reason) in [
+            ("http_req_duration.p95 500ms", "expected one of"),
+            ("latency.p95 < 1s", "unknown metric 'latency.p95'"),
+            ("http_req_duration.p42 < 1s", "unknown statistic 'p42'"),
+            ("http_req_duration < 1s", "needs a statistic"),
+            ("\"Login.p99 < 1s", "unterminated step name"),
+            ("error_rate < lots", "invalid number"),
+            ("p95 < 5 parsecs", "Unknown time unit"),
+        ] {
+            let err = Threshold::parse(expression).unwrap_err().to_string();
+            assert!(err.contains(reason), "{expression}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_abort_on_fail_table() {
+        let settings: ThresholdSettings = toml::from_str(
+            r#"
+rules = [
+  "error_rate < 1%",
+  { threshold = "p95 < 1s", abort_on_fail = true, delay_abort_eval = "30s" },
+]
+"#,
+        )
+        .unwrap();
+        assert!(!settings.rules[0].abort_on_fail);
+        let abort = &settings.rules[1];
+        assert!(abort.abort_on_fail);
+        assert_eq!(abort.abort_delay(), Duration::from_secs(30));
+        assert_eq!(abort.metric, ThresholdMetric::ResponseTime(LatencyStat::P95));
+
+        let err = toml::from_str::<ThresholdSettings>(
+            r#"rules = [{ threshold = "p95 < 1s", delay_abort_eval = "30s" }]"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("requires abort_on_fail"), "{err}");
+    }
+
+    #[test]
+    fn test_comparison_holds() {
+        assert!(Comparison::Less.holds(1.0, 2.0));
+        assert!(!Comparison::Less.holds(2.0, 2.0));
+        assert!(Comparison::LessOrEqual.holds(2.0, 2.0));
+        assert!(Comparison::GreaterOrEqual.holds(2.0, 2.0));
+        assert!(Comparison::Equal.holds(0.0, 0.0));
+    }
+}
@@ -0,0 +1,505 @@
+use crate::diagnostic::Diagnostic;
+use crate::duration::HumanDuration;
+use crate::error::{ConfigError, Result};
+use crate::scenario::{LoadProfile, Scenario, Step};
+use crate::template::{Segment, Template};
+use std::collections::{HashMap, HashSet};
+
+/// Methods the `http` protocol can send, in any case
+pub const HTTP_METHODS: [&str; 7] = ["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
+
+/// Extractor types with the parts of a response each can read
+const EXTRACTORS: [(&str, &[&str]); 6] = [
+    ("jsonpath", &["body"]),
+    ("xpath", &["body"]),
+    ("regex", &["body", "header", "status"]),
+    ("header", &["header"]),
+    ("status", &["status"]),
+    ("cookie", &["cookie"]),
+];
+
+impl Scenario {
+    /// Validate the scenario configuration, failing with the first problem
+    /// of [`Self::diagnostics`]
+    pub fn validate(&self) -> Result<()> {
+        self.diagnostics().into_iter().next().map_or(Ok(()), |diagnostic| Err(diagnostic.error))
+    }
+
+    /// Every problem in the scenario, in the order they are checked.
+    ///
+    /// Each one is located in its source file when the scenario was loaded
+    /// from source.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut problems = Problems { scenario: self, found: Vec::new() };
+        problems.check();
+        problems.found
+    }
+}
+
+struct Problems<'a> {
+    scenario: &'a Scenario,
+    found: Vec<Diagnostic>,
+}
+
+impl Problems<'_> {
+    fn push(&mut self, field: impl Into<String>, error: ConfigError) {
+        let field = field.into();
+        let location = self.scenario.sources.locate(&field);
+        self.found.push(Diagnostic { field, error, location });
+    }
+
+    fn invalid(&mut self, field: impl Into<String>, message: String) {
+        self.push(field, ConfigError::InvalidScenario(message));
+    }
+
+    fn check(&mut self) {
+        let scenario = self.scenario;
+        if scenario.scenario.name.is_empty() {
+            self.push("scenario.name", ConfigError::MissingField("scenario.name".to_string()));
+        }
+        if scenario.metrics.snapshot_interval().is_zero() {
+            self.invalid(
+                "metrics.snapshot_interval",
+                "metrics.snapshot_interval must be greater than 0".to_string(),
+            );
+        }
+        if scenario.metrics.expected_interval.is_some_and(|d| d.as_duration().is_zero()) {
+            self.invalid(
+                "metrics.expected_interval",
+                "metrics.expected_interval must be greater than 0".to_string(),
+            );
+        }
+
+        if scenario.thresholds.abort_window().is_zero() {
+            self.invalid(
+                "thresholds.abort_window",
+                "thresholds.abort_window must be greater than 0".to_string(),
+            );
+        }
+        self.check_thresholds();
+
+        // Data columns are variables available to every step; without all
+        // of them undefined variables cannot be told apart
+        let mut data_columns = Vec::new();
+        let mut data_loaded = true;
+        for (i, source) in scenario.data.iter().enumerate() {
+            match source.load(scenario.base_dir.as_deref()) {
+                Ok(data) => data_columns.extend(data.columns),
+                Err(e) => {
+                    data_loaded = false;
+                    self.push(format!("data[{i}]"), e);
+                }
+            }
+        }
+        let data_columns = data_loaded.then_some(&data_columns[..]);
+
+        if scenario.scenarios.is_empty() {
+            match &scenario.load_profile {
+                Some(load_profile) => self.check_load_profile("load_profile", load_profile),
+                None => {
+                    self.push(
+                        "load_profile",
+                        ConfigError::MissingField("load_profile".to_string()),
+                    );
+                }
+            }
+            self.check_steps("steps", &scenario.steps, data_columns);
+            return;
+        }
+
+        if scenario.load_profile.is_some() || !scenario.steps.is_empty() {
+            let field = if scenario.load_profile.is_some() { "load_profile" } else { "steps" };
+            self.invalid(
+                field,
+                "Top-level load_profile and steps cannot be combined with [[scenarios]]"
+                    .to_string(),
+            );
+        }
+
+        let mut names = HashSet::new();
+        for (i, named) in scenario.scenarios.iter().enumerate() {
+            let path = format!("scenarios[{i}]");
+            if named.name.is_empty() {
+                self.push(
+                    format!("{path}.name"),
+                    ConfigError::MissingField(format!("{path}.name")),
+                );
+            } else if !names.insert(named.name.as_str()) {
+                self.invalid(
+                    format!("{path}.name"),
+                    format!("Duplicate scenario name '{}'", named.name),
+                );
+            }
+            self.check_load_profile(&format!("{path}.load_profile"), &named.load_profile);
+            self.check_steps(&format!("{path}.steps"), &named.steps, data_columns);
+        }
+    }
+
+    /// Reject step-scoped thresholds naming a step that does not exist
+    fn check_thresholds(&mut self) {
+        let named = self.scenario.named_scenarios();
+        for (i, threshold) in self.scenario.thresholds.rules.iter().enumerate() {
+            let Some(target) = &threshold.step else { continue };
+            let exists = named.iter().any(|scenario| {
+                scenario.steps.iter().any(|step| {
+                    step.name == *target || format!("{}/{}", scenario.name, step.name) == *target
+                })
+            });
+            if !exists {
+                self.push(
+                    format!("thresholds.rules[{i}]"),
+                    ConfigError::InvalidThreshold {
+                        expression: threshold.expression.clone(),
+                        reason: format!("no step named '{target}'"),
+                    },
+                );
+            }
+        }
+    }
+
+    fn check_load_profile(&mut self, path: &str, load_profile: &LoadProfile) {
+        let is_zero = |duration: &HumanDuration| duration.as_duration().is_zero();
+        let positive = |problems: &mut Self, field: &str, ok: bool| {
+            if !ok {
+                problems.invalid(
+                    format!("{path}.{field}"),
+                    format!("{path}.{field} must be greater than 0"),
+                );
+            }
+        };
+        match load_profile {
+            LoadProfile::Constant { users, duration, ramp_up } => {
+                positive(self, "users", *users > 0);
+                positive(self, "duration", !is_zero(duration));
+                if ramp_up.is_some_and(|ramp_up| ramp_up.as_duration() > duration.as_duration()) {
+                    self.invalid(
+                        format!("{path}.ramp_up"),
+                        format!("{path}.ramp_up is longer than {path}.duration"),
+                    );
+                }
+            }
+            LoadProfile::Ramp { from, to, duration } => {
+                if *from == 0 && *to == 0 {
+                    self.invalid(
+                        format!("{path}.to"),
+                        format!("{path}.from and {path}.to cannot both be 0"),
+                    );
+                }
+                positive(self, "duration", !is_zero(duration));
+            }
+            LoadProfile::Stepped { steps } => {
+                if steps.is_empty() {
+                    self.invalid(
+                        format!("{path}.steps"),
+                        format!("{path}.steps must have at least one stage"),
+                    );
+                }
+                for (i, stage) in steps.iter().enumerate() {
+                    positive(self, &format!("steps[{i}].duration"), !is_zero(&stage.duration));
+                }
+            }
+            LoadProfile::Spike { spike_duration, total_duration, .. } => {
+                positive(self, "total_duration", !is_zero(total_duration));
+                if spike_duration.as_duration() > total_duration.as_duration() {
+                    self.invalid(
+                        format!("{path}.spike_duration"),
+                        format!("{path}.spike_duration is longer than {path}.total_duration"),
+                    );
+                }
+            }
+            LoadProfile::ConstantArrivalRate { rate, duration, max_vus } => {
+                positive(self, "rate", *rate > 0.0);
+                positive(self, "duration", !is_zero(duration));
+                positive(self, "max_vus", *max_vus > 0);
+            }
+            LoadProfile::RampingArrivalRate { from, to, duration, max_vus } => {
+                if *from < 0.0 || *to < 0.0 || (*from == 0.0 && *to == 0.0) {
+                    self.invalid(
+                        format!("{path}.to"),
+                        format!("{path}.from and {path}.to must be non-negative and not both 0"),
+                    );
+                }
+                positive(self, "duration", !is_zero(duration));
+                positive(self, "max_vus", *max_vus > 0);
+            }
+            LoadProfile::PerVuIterations { vus, iterations, max_duration }
+            | LoadProfile::SharedIterations { vus, iterations, max_duration } => {
+                if *vus == 0 || *iterations == 0 {
+                    let field = if *vus == 0 { "vus" } else { "iterations" };
+                    self.invalid(
+                        format!("{path}.{field}"),
+                        format!("{path}.vus and {path}.iterations must be greater than 0"),
+                    );
+                }
+                positive(self, "max_duration", !max_duration.as_ref().is_some_and(is_zero));
+            }
+        }
+    }
+
+    /// `data_columns` is `None` when a data file could not be read
+    fn check_steps(&mut self, path: &str, steps: &[Step], data_columns: Option<&[String]>) {
+        if steps.is_empty() {
+            self.invalid(path, "Scenario must have at least one step".to_string());
+            return;
+        }
+
+        let mut names = HashMap::new();
+        for (i, step) in steps.iter().enumerate() {
+            let at = format!("{path}[{i}]");
+            if step.name.is_empty() {
+                self.push(format!("{at}.name"), ConfigError::MissingField(format!("{at}.name")));
+            } else if let Some(first) = names.get(step.name.as_str()) {
+                self.invalid(
+                    format!("{at}.name"),
+                    format!("{at}.name '{}' is already used by {path}[{first}]", step.name),
+                );
+            } else {
+                names.insert(step.name.as_str(), i);
+            }
+            if step.url.is_empty() {
+                self.push(format!("{at}.url"), ConfigError::MissingField(format!("{at}.url")));
+            }
+            if step.protocol.eq_ignore_ascii_case("http") {
+                self.check_http(&at, step);
+            }
+            self.check_extractors(&at, step);
+        }
+
+        self.check_templates(path, steps, data_columns);
+    }
+
+    fn check_http(&mut self, at: &str, step: &Step) {
+        if !HTTP_METHODS.iter().any(|method| method.eq_ignore_ascii_case(&step.method)) {
+            self.invalid(
+                format!("{at}.method"),
+                format!(
+                    "{at}.method '{}' is not an HTTP method, expected one of {}",
+                    step.method,
+                    HTTP_METHODS.join(", ")
+                ),
+            );
+        }
+        if let Some(reason) = url_problem(&step.url) {
+            self.invalid(
+                format!("{at}.url"),
+                format!("{at}.url '{}' is not a valid URL: {reason}", step.url),
+            );
+        }
+    }
+
+    fn check_extractors(&mut self, at: &str, step: &Step) {
+        let Some(extract) = &step.extract else { return };
+        let mut extract: Vec<_> = extract.iter().collect();
+        extract.sort_by_key(|(variable, _)| *variable);
+        for (variable, extractor) in extract {
+            let field = format!("{at}.extract.{variable}");
+            let kind = extractor.extractor_type.to_lowercase();
+            let Some((_, sources)) = EXTRACTORS.iter().find(|(name, _)| *name == kind) else {
+                let kinds: Vec<&str> = EXTRACTORS.iter().map(|(name, _)| *name).collect();
+                self.invalid(
+                    format!("{field}.type"),
+                    format!(
+                        "{field}.type '{}' is not an extractor type, expected one of {}",
+                        extractor.extractor_type,
+                        kinds.join(", ")
+                    ),
+                );
+                continue;
+            };
+            if !sources.contains(&extractor.from.to_lowercase().as_str()) {
+                self.invalid(
+                    format!("{field}.from"),
+                    format!(
+                        "{field}.from '{}' cannot be read by a {kind} extractor, expected {}",
+                        extractor.from,
+                        sources.join(" or ")
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Parse every templated field and report references to variables that
+    /// no step in the scenario extracts and no data file provides
+    fn check_templates(&mut self, path: &str, steps: &[Step], data_columns: Option<&[String]>) {
+        let defined: HashSet<&str> = steps
+            .iter()
+            .filter_map(|s| s.extract.as_ref())
+            .flat_map(|e| e.keys())
+            .chain(data_columns.unwrap_or_default())
+            .map(String::as_str)
+            .collect();
+
+        for (i, step) in steps.iter().enumerate() {
+            for (field, source) in step.templated_fields() {
+                let at = format!("{path}[{i}].{field}");
+                let template = match Template::parse(source) {
+                    Ok(template) => template,
+                    Err(ConfigError::InvalidTemplate(reason)) => {
+                        let error = ConfigError::InvalidTemplate(format!("{at}: {reason}"));
+                        self.push(at, error);
+                        continue;
+                    }
+                    Err(other) => {
+                        self.push(at, other);
+                        continue;
+                    }
+                };
+                if data_columns.is_none() {
+                    continue;
+                }
+                let mut reported = HashSet::new();
+                for name in template.variables() {
+                    if !defined.contains(name) && reported.insert(name) {
+                        self.invalid(&at, format!("{at} references undefined variable '{name}'"));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Why `url` cannot be requested, if it is malformed around its placeholders.
+///
+/// A URL starting with a placeholder may take its scheme and host from it,
+/// so it is not checked.
+fn url_problem(url: &str) -> Option<String> {
+    let template = Template::parse(url).ok()?;
+    match template.segments().first() {
+        None | Some(Segment::Variable(_) | Segment::Builtin(_)) => return None,
+        Some(Segment::Literal(_)) => {}
+    }
+    // "0" is valid as a host, a port and a path alike
+    let sample: String = template
+        .segments()
+        .iter()
+        .map(|segment| match segment {
+            Segment::Literal(text) => text.as_str(),
+            Segment::Variable(_) | Segment::Builtin(_) => "0",
+        })
+        .collect();
+    match url::Url::parse(&sample) {
+        Err(e) => Some(e.to_string()),
+        Ok(parsed) if !matches!(parsed.scheme(), "http" | "https") => {
+            Some(format!("unsupported scheme '{}', expected http or https", parsed.scheme()))
+        }
+        Ok(_) => None,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::{ConfigError, Scenario};
+
+    const BROKEN: &str = r#"[scenario]
+name = "Broken"
+
+[load_profile]
+type = "spike"
+baseline = 1
+peak = 10
+spike_duration = "2m"
+total_duration = "1m"
+
+[[steps]]
+name = "Home"
+protocol = "http"
+method = "GRAB"
+url = "https://example.com:port/"
+
+[[steps]]
+name = "Home"
+protocol = "http"
+method = "get"
+url = "ftp://{{host}}/files"
+headers = { Authorization = "Bearer {{token}}", X-Trace = "{{__nope}}" }
+extract = { id = { from = "header", type = "jsonpath", expr = "$.id" } }
+body = "{{user}} {{user}}"
+"#;
+
+    #[test]
+    fn test_every_problem_is_reported_with_its_location() {
+        let scenario = Scenario::from_toml(BROKEN).unwrap();
+        let diagnostics = scenario.diagnostics();
+        let found: Vec<(&str, usize)> = diagnostics
+            .iter()
+            .map(|d| (d.field.as_str(), d.location.as_ref().unwrap().line))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("load_profile.spike_duration", 8),
+                ("steps[0].method", 14),
+                ("steps[0].url", 15),
+                ("steps[1].name", 18),
+                ("steps[1].url", 21),
+                ("steps[1].extract.id.from", 23),
+                ("steps[1].url", 21),
+                ("steps[1].headers.Authorization", 22),
+                ("steps[1].headers.X-Trace", 22),
+                ("steps[1].body", 24),
+            ]
+        );
+
+        let messages: Vec<String> = diagnostics.iter().map(|d| d.error.to_string()).collect();
+        assert!(messages[1].contains("'GRAB' is not an HTTP method"), "{}", messages[1]);
+        assert!(messages[2].ends_with("invalid port number"), "{}", messages[2]);
+        assert!(messages[3].contains("already used by steps[0]"), "{}", messages[3]);
+        assert!(messages[4].contains("unsupported scheme 'ftp'"), "{}", messages[4]);
+        assert!(messages[5].contains("cannot be read by a jsonpath extractor, expected body"));
+        assert!(messages[6].ends_with("undefined variable 'host'"));
+        assert!(messages[7].ends_with("undefined variable 'token'"));
+        assert!(matches!(diagnostics[8].error, ConfigError::InvalidTemplate(_)));
+        assert!(messages[9].ends_with("undefined variable 'user'"));
+        assert_eq!(messages.len(), 10);
+
+        // validate() keeps failing with the first problem
+        let err = scenario.validate().unwrap_err().to_string();
+        assert_eq!(err, messages[0]);
+        assert_eq!(diagnostics[1].location.as_ref().unwrap().column, 10);
+    }
+
+    #[test]
+    fn test_load_profile_checks() {
+        let diagnostics = |profile: &str| {
+            let scenario = Scenario::from_toml(&format!(
+                "[scenario]\nname = \"P\"\n\n[load_profile]\n{profile}\n\n[[steps]]\nname = \"A\"\n\
+                 protocol = \"http\"\nmethod = \"GET\"\nurl = \"http://localhost/\"\n"
+            ))
+            .unwrap();
+            scenario.diagnostics().into_iter().map(|d| d.field).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            diagnostics("type = \"ramp\"\nfrom = 0\nto = 0\nduration = \"0s\""),
+            ["load_profile.to", "load_profile.duration"]
+        );
+        assert_eq!(diagnostics("type = \"stepped\"\nsteps = []"), ["load_profile.steps"]);
+        assert_eq!(
+            diagnostics(
+                "type = \"stepped\"\nsteps = [{ users = 1, duration = \"1s\" }, \
+                 { users = 2, duration = \"0s\" }]"
+            ),
+            ["load_profile.steps[1].duration"]
+        );
+        assert_eq!(
+            diagnostics("type = \"constant\"\nusers = 1\nduration = \"10s\"\nramp_up = \"1m\""),
+            ["load_profile.ramp_up"]
+        );
+        assert!(diagnostics("type = \"constant\"\nusers = 5\nduration = \"10s\"").is_empty());
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let toml = BROKEN.replace("method = \"get\"", "methd = \"get\"");
+        let err = Scenario::from_toml(&toml).unwrap_err().to_string();
+        assert!(err.contains("line 20"), "{err}");
+        assert!(err.contains("unknown field `methd`"), "{err}");
+
+        let yaml = "scenario:\n  name: Y\n  title: oops\nload_profile:\n  type: constant\n  \
+                    users: 1\n  duration: 1s\nsteps: []\n";
+        let err = Scenario::from_yaml(yaml).unwrap_err().to_string();
+        assert!(err.starts_with("Failed to parse YAML at line 3, column 3: "), "{err}");
+        assert!(err.contains("unknown field `title`"), "{err}");
+    }
+}
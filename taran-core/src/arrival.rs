@@ -0,0 +1,207 @@
+use crate::control::RunContext;
+use crate::plan::ScenarioPlan;
+use crate::vu::VirtualUser;
+use std::sync::Arc;
+use std::time::Duration;
+use taran_config::LoadProfile as LoadProfileConfig;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+/// Target iteration rate changing linearly from `from` to `to` per second.
+///
+/// A constant rate is the special case `from == to`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrivalRateProfile {
+    pub from: f64,
+    pub to: f64,
+    pub duration: Duration,
+}
+
+impl ArrivalRateProfile {
+    /// Build the arrival-rate profile for open-loop `[load_profile]` types.
+    ///
+    /// Returns `None` for closed-loop profiles, which are driven by VU count.
+    pub const fn from_config(config: &LoadProfileConfig) -> Option<Self> {
+        match config {
+            LoadProfileConfig::ConstantArrivalRate { rate, duration, .. } => {
+                Some(Self { from: *rate, to: *rate, duration: duration.as_duration() })
+            }
+            LoadProfileConfig::RampingArrivalRate { from, to, duration, .. } => {
+                Some(Self { from: *from, to: *to, duration: duration.as_duration() })
+            }
+            _ => None,
+        }
+    }
+
+    /// Target rate in iterations per second at `elapsed`
+    pub fn rate_at(&self, elapsed: Duration) -> f64 {
+        let d = self.duration.as_secs_f64();
+        if d == 0.0 {
+            return self.to;
+        }
+        let progress = (elapsed.as_secs_f64() / d).min(1.0);
+        (self.to - self.from).mul_add(progress, self.from)
+    }
+
+    /// Offset from test start at which iteration `k` (0-based) is due.
+    ///
+    /// Solves `N(t) = k` where `N` is the integral of the rate, so the
+    /// schedule is exact and does not accumulate rounding drift. Returns
+    /// `None` once the offset falls outside the profile's duration.
+    pub fn arrival_time(&self, k: u64) -> Option<Duration> {
+        let k = k as f64;
+        let d = self.duration.as_secs_f64();
+        let slope = if d == 0.0 { 0.0 } else { (self.to - self.from) / d };
+
+        let t = if slope.abs() < f64::EPSILON {
+            if self.from <= 0.0 {
+                return None;
+            }
+            k / self.from
+        } else {
+            // slope/2 * t^2 + from * t - k = 0
+            let discriminant = self.from.mul_add(self.from, 2.0 * slope * k);
+            if discriminant < 0.0 {
+                return None;
+            }
+            (discriminant.sqrt() - self.from) / slope
+        };
+
+        if !t.is_finite() || t < 0.0 || t >= d {
+            return None;
+        }
+        Some(Duration::from_secs_f64(t))
+    }
+}
+
+/// Open-loop executor: starts iterations on a fixed schedule whether or not
+/// earlier ones have finished.
+///
+/// At most `max_vus` iterations are in flight. When every VU is busy the next
+/// iteration waits for one to free up, but its latency is still measured from
+/// the time it was scheduled, which keeps coordinated omission out of the
+/// results.
+pub struct ArrivalRateExecutor {
+    profile: ArrivalRateProfile,
+    max_vus: usize,
+}
+
+impl ArrivalRateExecutor {
+    pub const fn new(profile: ArrivalRateProfile, max_vus: usize) -> Self {
+        Self { profile, max_vus }
+    }
+
+    /// Build the executor for open-loop `[load_profile]` types
+    pub fn from_config(config: &LoadProfileConfig) -> Option<Self> {
+        let max_vus = match config {
+            LoadProfileConfig::ConstantArrivalRate { max_vus, .. }
+            | LoadProfileConfig::RampingArrivalRate { max_vus, .. } => *max_vus,
+            _ => return None,
+        };
+        ArrivalRateProfile::from_config(config).map(|profile| Self::new(profile, max_vus))
+    }
+
+    /// Dispatch iterations until the schedule ends or the test is stopped,
+    /// then wait for the in-flight ones to complete.
+    pub async fn run(self, plan: Arc<ScenarioPlan>, run: RunContext) {
+        let (idle_tx, mut idle_rx) = mpsc::unbounded_channel::<VirtualUser>();
+        let start = Instant::now();
+        let mut spawned = 0;
+        let mut delayed = 0u64;
+        let mut k = 0;
+
+        while let Some(offset) = self.profile.arrival_time(k) {
+            let scheduled = start + offset;
+            tokio::select! {
+                () = tokio::time::sleep_until(scheduled) => {}
+                () = run.stop.stopped() => break,
+            }
+
+            let vu = match idle_rx.try_recv() {
+                Ok(vu) => vu,
+                Err(_) if spawned < self.max_vus => {
+                    spawned += 1;
+                    VirtualUser::new(spawned - 1, Arc::clone(&plan), run.clone())
+                }
+                Err(_) => {
+                    delayed += 1;
+                    debug!("All {} VUs busy, iteration {k} is waiting", self.max_vus);
+                    tokio::select! {
+                        idle = idle_rx.recv() => match idle {
+                            Some(vu) => vu,
+                            None => break,
+                        },
+                        () = run.stop.stopped() => break,
+                    }
+                }
+            };
+
+            let idle_tx = idle_tx.clone();
+            tokio::spawn(async move {
+                let mut vu = vu;
+                vu.run_scheduled_iteration(scheduled).await;
+                // The receiver outlives every iteration; a send error only
+                // means the run is already shutting down
+                let _ = idle_tx.send(vu);
+            });
+            k += 1;
+        }
+
+        info!("Arrival schedule finished: {k} iterations started on {spawned} VUs");
+        if delayed > 0 {
+            warn!("{delayed} iterations started late because all {} VUs were busy", self.max_vus);
+        }
+
+        // Every in-flight task holds a sender; once they all finish the
+        // channel closes and `recv` returns `None`
+        drop(idle_tx);
+        while idle_rx.recv().await.is_some() {}
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_arrival_times() {
+        let profile = ArrivalRateProfile { from: 10.0, to: 10.0, duration: Duration::from_secs(1) };
+        assert_eq!(profile.arrival_time(0), Some(Duration::ZERO));
+        assert_eq!(profile.arrival_time(5), Some(Duration::from_millis(500)));
+        assert_eq!(profile.arrival_time(9), Some(Duration::from_millis(900)));
+        assert_eq!(profile.arrival_time(10), None);
+    }
+
+    #[test]
+    fn test_ramping_arrival_times() {
+        // 0 -> 20/s over 10s schedules 100 iterations, denser towards the end
+        let profile = ArrivalRateProfile { from: 0.0, to: 20.0, duration: Duration::from_secs(10) };
+        let at = |k| profile.arrival_time(k).unwrap().as_secs_f64();
+        let first_gap = at(1) - at(0);
+        let last_gap = at(99) - at(98);
+        assert!(first_gap > last_gap);
+        assert!(profile.arrival_time(99).is_some());
+        assert!(profile.arrival_time(100).is_none());
+        assert!((profile.rate_at(Duration::from_secs(5)) - 10.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_ramping_down_to_zero() {
+        let profile = ArrivalRateProfile { from: 10.0, to: 0.0, duration: Duration::from_secs(2) };
+        // Total arrivals = area under the rate curve = 10
+        assert!(profile.arrival_time(9).is_some());
+        assert!(profile.arrival_time(10).is_none());
+    }
+
+    #[test]
+    fn test_closed_loop_config_is_not_arrival_rate() {
+        let config = LoadProfileConfig::Ramp {
+            from: 1,
+            to: 2,
+            duration: taran_config::HumanDuration(Duration::from_secs(1)),
+        };
+        assert!(ArrivalRateExecutor::from_config(&config).is_none());
+    }
+}
@@ -0,0 +1,32 @@
+use crate::protocols::ProtocolError;
+use taran_config::ClientMode;
+use taran_protocols::HttpClient;
+
+/// Hands out HTTP clients to the VUs of one scenario according to its
+/// [`ClientMode`]
+#[derive(Debug, Clone)]
+pub enum ClientPool {
+    PerVu,
+    /// The one client every VU clones; clones share the connection pool
+    Shared(HttpClient),
+    NoKeepAlive,
+}
+
+impl ClientPool {
+    pub fn new(mode: ClientMode) -> Result<Self, ProtocolError> {
+        Ok(match mode {
+            ClientMode::PerVu => Self::PerVu,
+            ClientMode::Shared => Self::Shared(HttpClient::new()?),
+            ClientMode::NoKeepAlive => Self::NoKeepAlive,
+        })
+    }
+
+    /// Client for a newly started VU, kept for the VU's lifetime
+    pub fn client_for_vu(&self) -> Result<HttpClient, ProtocolError> {
+        match self {
+            Self::PerVu => HttpClient::new(),
+            Self::Shared(client) => Ok(client.clone()),
+            Self::NoKeepAlive => HttpClient::without_keep_alive(),
+        }
+    }
+}
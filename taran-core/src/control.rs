@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use taran_metrics::HdrCollector;
+use tokio::sync::Notify;
+
+/// Test-wide stop flag that can also be awaited.
+///
+/// Raised when something decides the whole run must end early, e.g. a data
+/// feeder configured with `on_exhausted = "stop_test"` runs dry.
+#[derive(Debug, Clone, Default)]
+pub struct StopSignal {
+    inner: Arc<StopInner>,
+}
+
+#[derive(Debug, Default)]
+struct StopInner {
+    stopped: AtomicBool,
+    notify: Notify,
+}
+
+impl StopSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every executor and VU to stop
+    pub fn stop(&self) {
+        self.inner.stopped.store(true, Ordering::Release);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.inner.stopped.load(Ordering::Acquire)
+    }
+
+    /// Resolve once [`stop`](Self::stop) has been called
+    pub async fn stopped(&self) {
+        loop {
+            // Register before checking the flag so a concurrent `stop` is not missed
+            let notified = self.inner.notify.notified();
+            if self.is_stopped() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// State shared by every executor and VU of a run
+#[derive(Debug, Clone)]
+pub struct RunContext {
+    pub collector: HdrCollector,
+    pub stop: StopSignal,
+    /// Back-fill interval for closed-loop response times
+    pub expected_interval: Option<Duration>,
+}
+
+impl RunContext {
+    pub fn new(collector: HdrCollector) -> Self {
+        Self { collector, stop: StopSignal::new(), expected_interval: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_stopped_wakes_waiters() {
+        let signal = StopSignal::new();
+        let waiter = tokio::spawn({
+            let signal = signal.clone();
+            async move { signal.stopped().await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!signal.is_stopped());
+        signal.stop();
+        assert!(tokio::time::timeout(Duration::from_secs(1), waiter).await.is_ok());
+        // Already stopped: resolves immediately
+        signal.stopped().await;
+    }
+}
@@ -17,6 +17,12 @@ pub enum CoreError {
     #[error("Metrics error: {0}")]
     MetricsError(#[from] taran_metrics::MetricsError),
 
+    #[error("Unknown protocol '{protocol}' in step '{step}' (supported: {supported})")]
+    UnknownProtocol { step: String, protocol: String, supported: String },
+
+    #[error("Invalid extractor '{variable}' in step '{step}': {reason}")]
+    InvalidExtractor { step: String, variable: String, reason: String },
+
     #[error("Tokio runtime error: {0}")]
     RuntimeError(String),
 }
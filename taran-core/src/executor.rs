@@ -0,0 +1,53 @@
+use crate::arrival::ArrivalRateExecutor;
+use crate::control::RunContext;
+use crate::error::{CoreError, Result};
+use crate::iterations::IterationsExecutor;
+use crate::plan::ScenarioPlan;
+use crate::profile;
+use crate::scheduler::VuScheduler;
+use crate::traits::LoadProfile;
+use crate::vu::VirtualUser;
+use std::sync::Arc;
+use taran_config::LoadProfile as LoadProfileConfig;
+
+/// Strategy used to generate load for a scenario
+pub enum Executor {
+    /// Closed-loop: the number of live VUs follows a load profile and each
+    /// VU starts its next iteration when the previous one finishes
+    ClosedLoop(Box<dyn LoadProfile>),
+    /// Open-loop: iterations start at a target rate regardless of how long
+    /// earlier ones take
+    ArrivalRate(ArrivalRateExecutor),
+    /// A fixed number of iterations, either per VU or from a shared pool
+    Iterations(IterationsExecutor),
+}
+
+impl Executor {
+    /// Select the executor for a scenario's `[load_profile]` section
+    pub fn from_config(config: &LoadProfileConfig) -> Result<Self> {
+        if let Some(executor) = ArrivalRateExecutor::from_config(config) {
+            return Ok(Self::ArrivalRate(executor));
+        }
+        if let Some(executor) = IterationsExecutor::from_config(config) {
+            return Ok(Self::Iterations(executor));
+        }
+        profile::from_config(config).map(Self::ClosedLoop).ok_or_else(|| {
+            CoreError::ExecutionFailed(format!("Unsupported load profile: {config:?}"))
+        })
+    }
+
+    /// Generate load until the executor's schedule ends
+    pub async fn run(self, plan: Arc<ScenarioPlan>, run: RunContext) {
+        match self {
+            Self::ClosedLoop(profile) => {
+                let stop = run.stop.clone();
+                let scheduler = VuScheduler::new(move |id, vu_stop| {
+                    VirtualUser::new(id, Arc::clone(&plan), run.clone()).run(vu_stop)
+                });
+                scheduler.run(profile.as_ref(), &stop).await;
+            }
+            Self::ArrivalRate(executor) => executor.run(plan, run).await,
+            Self::Iterations(executor) => executor.run(plan, run).await,
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use crate::error::{CoreError, Result};
+use jsonpath_rust::parser::model::JpQuery;
+use jsonpath_rust::parser::parse_json_path;
+use jsonpath_rust::query::js_path_process;
+use regex::Regex;
+use serde_json::Value;
+use taran_config::Extractor;
+use taran_protocols::HttpResponse;
+
+/// Part of the response a regex extractor is applied to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    Body,
+    /// Every header rendered as a `name: value` line
+    Headers,
+    Status,
+}
+
+#[derive(Debug, Clone)]
+enum ExtractorKind {
+    JsonPath(JpQuery),
+    Regex(Source, Regex),
+    /// sxd-xpath expressions are not `Send`, so only the validated
+    /// source is kept and it is compiled again on use
+    XPath(String),
+    /// Lower-cased header name
+    Header(String),
+    Status,
+    Cookie(String),
+}
+
+/// A step extractor compiled once at scenario load
+#[derive(Debug, Clone)]
+pub struct CompiledExtractor {
+    /// Variable the extracted value is stored under
+    pub variable: String,
+    pub required: bool,
+    expr: String,
+    kind: ExtractorKind,
+}
+
+impl CompiledExtractor {
+    /// Compile an extractor, rejecting unknown `from`/`type` combinations
+    /// and malformed expressions.
+    pub fn compile(step: &str, variable: &str, extractor: &Extractor) -> Result<Self> {
+        let invalid = |reason: String| CoreError::InvalidExtractor {
+            step: step.to_string(),
+            variable: variable.to_string(),
+            reason,
+        };
+        let from = extractor.from.to_lowercase();
+        let expr = extractor.expr.clone();
+
+        let kind = match extractor.extractor_type.to_lowercase().as_str() {
+            "jsonpath" => {
+                require_source(&from, "body").map_err(invalid)?;
+                let query = parse_json_path(&expr)
+                    .map_err(|e| invalid(format!("invalid JSONPath '{expr}': {e}")))?;
+                ExtractorKind::JsonPath(query)
+            }
+            "xpath" => {
+                require_source(&from, "body").map_err(invalid)?;
+                match sxd_xpath::Factory::new().build(&expr) {
+                    Ok(Some(_)) => ExtractorKind::XPath(expr.clone()),
+                    Ok(None) => return Err(invalid("empty XPath expression".to_string())),
+                    Err(e) => return Err(invalid(format!("invalid XPath '{expr}': {e}"))),
+                }
+            }
+            "regex" => {
+                let source = match from.as_str() {
+                    "body" => Source::Body,
+                    "header" => Source::Headers,
+                    "status" => Source::Status,
+                    other => {
+                        return Err(invalid(format!("regex cannot be applied to '{other}'")));
+                    }
+                };
+                let regex =
+                    Regex::new(&expr).map_err(|e| invalid(format!("invalid regex: {e}")))?;
+                ExtractorKind::Regex(source, regex)
+            }
+            "header" => {
+                require_source(&from, "header").map_err(invalid)?;
+                require_expr(&expr, "header name").map_err(invalid)?;
+                ExtractorKind::Header(expr.to_lowercase())
+            }
+            "status" => {
+                require_source(&from, "status").map_err(invalid)?;
+                ExtractorKind::Status
+            }
+            "cookie" => {
+                require_source(&from, "cookie").map_err(invalid)?;
+                require_expr(&expr, "cookie name").map_err(invalid)?;
+                ExtractorKind::Cookie(expr.clone())
+            }
+            other => return Err(invalid(format!("unknown extractor type '{other}'"))),
+        };
+
+        Ok(Self { variable: variable.to_string(), required: extractor.required, expr, kind })
+    }
+
+    /// Extract a value from the response, or explain why nothing matched
+    pub fn extract(&self, response: &HttpResponse) -> std::result::Result<String, String> {
+        match &self.kind {
+            ExtractorKind::JsonPath(query) => {
+                let json: Value = serde_json::from_str(&response.body)
+                    .map_err(|e| format!("response body is not JSON: {e}"))?;
+                let matches = js_path_process(query, &json)
+                    .map_err(|e| format!("JSONPath '{}' failed: {e}", self.expr))?;
+                matches
+                    .into_iter()
+                    .next()
+                    .map(|m| json_to_string(m.val()))
+                    .ok_or_else(|| format!("JSONPath '{}' matched nothing", self.expr))
+            }
+            ExtractorKind::Regex(source, regex) => {
+                let text = match source {
+                    Source::Body => response.body.clone(),
+                    Source::Headers => {
+                        let mut lines: Vec<String> =
+                            response.headers.iter().map(|(k, v)| format!("{k}: {v}")).collect();
+                        lines.sort();
+                        lines.join("\n")
+                    }
+                    Source::Status => response.status.to_string(),
+                };
+                let captures = regex
+                    .captures(&text)
+                    .ok_or_else(|| format!("regex '{}' matched nothing", self.expr))?;
+                // First capture group if the pattern has one, else the whole match
+                captures
+                    .get(1)
+                    .or_else(|| captures.get(0))
+                    .map(|m| m.as_str().to_string())
+                    .ok_or_else(|| format!("regex '{}' matched nothing", self.expr))
+            }
+            ExtractorKind::XPath(expr) => {
+                let package = sxd_document::parser::parse(&response.body)
+                    .map_err(|e| format!("response body is not XML: {e}"))?;
+                let document = package.as_document();
+                let value = sxd_xpath::evaluate_xpath(&document, expr)
+                    .map_err(|e| format!("XPath '{expr}' failed: {e}"))?;
+                match value {
+                    sxd_xpath::Value::Nodeset(ref nodes) if nodes.size() == 0 => {
+                        Err(format!("XPath '{expr}' matched nothing"))
+                    }
+                    other => Ok(other.string()),
+                }
+            }
+            ExtractorKind::Header(name) => response
+                .headers
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("header '{}' not present", self.expr)),
+            ExtractorKind::Status => Ok(response.status.to_string()),
+            ExtractorKind::Cookie(name) => response
+                .cookie(name)
+                .cloned()
+                .ok_or_else(|| format!("cookie '{name}' not set by response")),
+        }
+    }
+}
+
+fn require_source(from: &str, expected: &str) -> std::result::Result<(), String> {
+    if from == expected {
+        Ok(())
+    } else {
+        Err(format!("expected from = \"{expected}\", got \"{from}\""))
+    }
+}
+
+fn require_expr(expr: &str, what: &str) -> std::result::Result<(), String> {
+    if expr.is_empty() {
+        Err(format!("expr must be the {what}"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Strings are stored unquoted; any other JSON value keeps its JSON form
+fn json_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    fn response(body: &str) -> HttpResponse {
+        let mut headers = HashMap::new();
+        headers.insert("location".to_string(), "/orders/42".to_string());
+        let mut cookies = HashMap::new();
+        cookies.insert("session".to_string(), "s3cr3t".to_string());
+        HttpResponse {
+            status: 201,
+            headers,
+            cookies,
+            body: body.to_string(),
+            duration: Duration::ZERO,
+            bytes_sent: 0,
+            bytes_received: 0,
+        }
+    }
+
+    fn extractor(from: &str, extractor_type: &str, expr: &str) -> CompiledExtractor {
+        let config = Extractor {
+            from: from.to_string(),
+            extractor_type: extractor_type.to_string(),
+            expr: expr.to_string(),
+            required: true,
+        };
+        CompiledExtractor::compile("step", "var", &config).unwrap()
+    }
+
+    #[test]
+    fn test_jsonpath() {
+        let resp = response(r#"{"token": "abc", "user": {"id": 7}, "items": [{"id": "x"}]}"#);
+        assert_eq!(extractor("body", "jsonpath", "$.token").extract(&resp).unwrap(), "abc");
+        assert_eq!(extractor("body", "jsonpath", "$.user.id").extract(&resp).unwrap(), "7");
+        assert_eq!(extractor("body", "jsonpath", "$.items[0].id").extract(&resp).unwrap(), "x");
+        assert!(extractor("body", "jsonpath", "$.missing").extract(&resp).is_err());
+    }
+
+    #[test]
+    fn test_regex() {
+        let resp = response("<input name=\"csrf\" value=\"tok-1\">");
+        let csrf = extractor("body", "regex", r#"name="csrf" value="([^"]+)""#);
+        assert_eq!(csrf.extract(&resp).unwrap(), "tok-1");
+        let order = extractor("header", "regex", r"location: /orders/(\d+)");
+        assert_eq!(order.extract(&resp).unwrap(), "42");
+        assert_eq!(extractor("status", "regex", r"2\d\d").extract(&resp).unwrap(), "201");
+    }
+
+    #[test]
+    fn test_xpath() {
+        let resp = response("<user><id>99</id><name>Ann</name></user>");
+        assert_eq!(extractor("body", "xpath", "/user/name").extract(&resp).unwrap(), "Ann");
+        assert!(extractor("body", "xpath", "/user/email").extract(&resp).is_err());
+    }
+
+    #[test]
+    fn test_header_status_cookie() {
+        let resp = response("");
+        assert_eq!(extractor("header", "header", "Location").extract(&resp).unwrap(), "/orders/42");
+        assert_eq!(extractor("status", "status", "").extract(&resp).unwrap(), "201");
+        assert_eq!(extractor("cookie", "cookie", "session").extract(&resp).unwrap(), "s3cr3t");
+        assert!(extractor("cookie", "cookie", "other").extract(&resp).is_err());
+    }
+
+    #[test]
+    fn test_compile_rejects_invalid_extractors() {
+        let config = |from: &str, extractor_type: &str, expr: &str| Extractor {
+            from: from.to_string(),
+            extractor_type: extractor_type.to_string(),
+            expr: expr.to_string(),
+            required: true,
+        };
+        assert!(CompiledExtractor::compile("s", "v", &config("header", "jsonpath", "$.a")).is_err());
+        assert!(CompiledExtractor::compile("s", "v", &config("body", "regex", "(")).is_err());
+        assert!(CompiledExtractor::compile("s", "v", &config("body", "css", "a")).is_err());
+        assert!(CompiledExtractor::compile("s", "v", &config("header", "header", "")).is_err());
+    }
+}
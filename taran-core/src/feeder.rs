@@ -0,0 +1,134 @@
+use crate::error::Result;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use taran_config::{DataSource, DataStrategy, OnExhausted};
+
+/// Rows of one `[[data]]` file, shared by every VU of the run.
+///
+/// Rows are read-only after load and the only shared mutable state is an
+/// atomic cursor, so handing out a row never takes a lock.
+#[derive(Debug)]
+pub struct DataFeeder {
+    name: String,
+    columns: Vec<String>,
+    rows: Vec<Vec<String>>,
+    strategy: DataStrategy,
+    on_exhausted: OnExhausted,
+    cursor: AtomicUsize,
+}
+
+impl DataFeeder {
+    /// Load the data file behind a `[[data]]` entry
+    pub fn load(source: &DataSource, base_dir: Option<&Path>) -> Result<Self> {
+        let data = source.load(base_dir)?;
+        Ok(Self {
+            name: source.label(),
+            columns: data.columns,
+            rows: data.rows,
+            strategy: source.strategy,
+            on_exhausted: source.on_exhausted,
+            cursor: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub const fn on_exhausted(&self) -> OnExhausted {
+        self.on_exhausted
+    }
+
+    /// Index of the row for a VU's next iteration, or `None` when the
+    /// source is exhausted and not configured to wrap.
+    ///
+    /// `assigned` is the VU's own slot, used by `unique_per_vu` to keep the
+    /// same row for every iteration of that VU.
+    pub fn next_row(&self, assigned: &mut Option<usize>) -> Option<usize> {
+        let len = self.rows.len();
+        match self.strategy {
+            DataStrategy::Random => Some(fastrand::usize(..len)),
+            DataStrategy::Circular => Some(self.cursor.fetch_add(1, Ordering::Relaxed) % len),
+            DataStrategy::Sequential => self.take(),
+            DataStrategy::UniquePerVu => {
+                if assigned.is_none() {
+                    *assigned = self.take();
+                }
+                *assigned
+            }
+        }
+    }
+
+    /// `(column, value)` pairs of a row
+    pub fn row(&self, index: usize) -> impl Iterator<Item = (&str, &str)> {
+        self.columns
+            .iter()
+            .map(String::as_str)
+            .zip(self.rows.get(index).into_iter().flatten().map(String::as_str))
+    }
+
+    /// Claim the next unused row
+    fn take(&self) -> Option<usize> {
+        let index = self.cursor.fetch_add(1, Ordering::Relaxed);
+        if index < self.rows.len() {
+            Some(index)
+        } else if self.on_exhausted == OnExhausted::Wrap {
+            Some(index % self.rows.len())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn users(strategy: DataStrategy, on_exhausted: OnExhausted) -> DataFeeder {
+        DataFeeder {
+            name: "users".to_string(),
+            columns: vec!["username".to_string()],
+            rows: vec![vec!["a".to_string()], vec!["b".to_string()], vec!["c".to_string()]],
+            strategy,
+            on_exhausted,
+            cursor: AtomicUsize::new(0),
+        }
+    }
+
+    fn draw(feeder: &DataFeeder, n: usize) -> Vec<Option<usize>> {
+        (0..n).map(|_| feeder.next_row(&mut None)).collect()
+    }
+
+    #[test]
+    fn test_sequential_exhausts() {
+        let feeder = users(DataStrategy::Sequential, OnExhausted::StopVu);
+        assert_eq!(draw(&feeder, 4), [Some(0), Some(1), Some(2), None]);
+        let wrapping = users(DataStrategy::Sequential, OnExhausted::Wrap);
+        assert_eq!(draw(&wrapping, 4), [Some(0), Some(1), Some(2), Some(0)]);
+    }
+
+    #[test]
+    fn test_circular_never_exhausts() {
+        let feeder = users(DataStrategy::Circular, OnExhausted::StopTest);
+        assert_eq!(draw(&feeder, 5), [Some(0), Some(1), Some(2), Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn test_random_stays_in_range() {
+        let feeder = users(DataStrategy::Random, OnExhausted::StopVu);
+        assert!(draw(&feeder, 100).into_iter().all(|row| row.is_some_and(|i| i < 3)));
+    }
+
+    #[test]
+    fn test_unique_per_vu_keeps_its_row() {
+        let feeder = users(DataStrategy::UniquePerVu, OnExhausted::StopVu);
+        let (mut vu0, mut vu1) = (None, None);
+        assert_eq!(feeder.next_row(&mut vu0), Some(0));
+        assert_eq!(feeder.next_row(&mut vu1), Some(1));
+        assert_eq!(feeder.next_row(&mut vu0), Some(0));
+        assert_eq!(feeder.next_row(&mut None), Some(2));
+        assert_eq!(feeder.next_row(&mut None), None);
+        assert_eq!(feeder.row(1).collect::<Vec<_>>(), [("username", "b")]);
+    }
+}
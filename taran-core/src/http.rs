@@ -0,0 +1,204 @@
+use crate::client::ClientPool;
+use crate::error::Result;
+use crate::extract::CompiledExtractor;
+use crate::model::{StepResult, VirtualUserContext};
+use crate::registry::StepSpec;
+use crate::session::Session;
+use crate::template;
+use crate::traits::Protocol;
+use async_trait::async_trait;
+use std::time::Instant;
+use taran_config::{Assertions, Step, Template};
+use taran_protocols::{HttpClient, HttpRequest, HttpResponse};
+use tracing::debug;
+
+/// HTTP step with its templates pre-parsed and extractors compiled, so the
+/// request hot path does no parsing
+#[derive(Debug)]
+pub struct HttpProtocol {
+    scenario: String,
+    step: Step,
+    url: Template,
+    headers: Vec<(String, Template)>,
+    body: Option<Template>,
+    extractors: Vec<CompiledExtractor>,
+    clients: ClientPool,
+}
+
+impl HttpProtocol {
+    pub fn new(spec: &StepSpec<'_>) -> Result<Self> {
+        let step = spec.step;
+        let mut extractors = Vec::new();
+        if let Some(extract) = &step.extract {
+            // Sort so variables are always assigned in the same order
+            let mut names: Vec<_> = extract.keys().collect();
+            names.sort();
+            for name in names {
+                extractors.push(CompiledExtractor::compile(&step.name, name, &extract[name])?);
+            }
+        }
+        let headers = step
+            .headers
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), Template::parse(value)?)))
+            .collect::<Result<_>>()?;
+        let body = step.body.as_deref().map(Template::parse).transpose()?;
+
+        Ok(Self {
+            scenario: spec.scenario.to_string(),
+            step: step.clone(),
+            url: Template::parse(&step.url)?,
+            headers,
+            body,
+            extractors,
+            clients: spec.clients.clone(),
+        })
+    }
+
+    /// [`ProtocolFactory`](crate::registry::ProtocolFactory) for `http` steps
+    pub fn factory(spec: &StepSpec<'_>) -> Result<Box<dyn Protocol>> {
+        Ok(Box::new(Self::new(spec)?))
+    }
+
+    /// The VU's client, created on its first HTTP request
+    fn client(&self, session: &mut Session) -> Result<HttpClient> {
+        if let Some(client) = session.get::<HttpClient>() {
+            return Ok(client.clone());
+        }
+        let client = self.clients.client_for_vu()?;
+        session.insert(client.clone());
+        Ok(client)
+    }
+
+    /// Render the step's url, headers and body for this VU
+    fn build_request(
+        &self,
+        context: &VirtualUserContext,
+    ) -> std::result::Result<HttpRequest, String> {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), template::render(value, context)?)))
+            .collect::<std::result::Result<_, String>>()?;
+
+        Ok(HttpRequest {
+            method: self.step.method.clone(),
+            url: template::render(&self.url, context)?,
+            headers,
+            body: self.body.as_ref().map(|body| template::render(body, context)).transpose()?,
+            timeout: self.step.timeout.map(|timeout| timeout.as_duration()),
+        })
+    }
+
+    /// Store every extracted value in the VU context.
+    ///
+    /// Returns an error for the first required extractor that matched nothing;
+    /// optional extractors that miss leave any previous value untouched.
+    fn extract_variables(
+        &self,
+        response: &HttpResponse,
+        context: &mut VirtualUserContext,
+    ) -> Option<String> {
+        let mut error = None;
+        for extractor in &self.extractors {
+            match extractor.extract(response) {
+                Ok(value) => context.set_variable(extractor.variable.clone(), value),
+                Err(reason) if extractor.required && error.is_none() => {
+                    error =
+                        Some(format!("Extraction of '{}' failed: {reason}", extractor.variable));
+                }
+                Err(reason) => {
+                    debug!("Optional extraction '{}' skipped: {reason}", extractor.variable);
+                }
+            }
+        }
+        error
+    }
+}
+
+#[async_trait]
+impl Protocol for HttpProtocol {
+    async fn execute(
+        &self,
+        session: &mut Session,
+        context: &mut VirtualUserContext,
+    ) -> Result<StepResult> {
+        let step = &self.step;
+        let client = self.client(session)?;
+        let start = Instant::now();
+
+        let request = match self.build_request(context) {
+            Ok(request) => request,
+            Err(e) => {
+                let error = format!("Template error: {e}");
+                return Ok(StepResult::failure(&self.scenario, &step.name, start.elapsed(), error));
+            }
+        };
+
+        let result = match client.execute(request).await {
+            Ok(response) => {
+                let success = response.is_success();
+                let duration = response.duration;
+
+                // Check assertions if present
+                let assertion_error = step
+                    .assertions
+                    .as_ref()
+                    .and_then(|assertions| check_assertions(assertions, &response));
+
+                let extraction_error = self.extract_variables(&response, context);
+                let error = assertion_error.or(extraction_error);
+
+                StepResult {
+                    scenario: self.scenario.clone(),
+                    step_name: step.name.clone(),
+                    success: success && error.is_none(),
+                    duration,
+                    error,
+                    status_code: Some(response.status),
+                    bytes_sent: response.bytes_sent,
+                    bytes_received: response.bytes_received,
+                    started_at: None,
+                    scheduled_at: None,
+                    expected_interval: None,
+                }
+            }
+            Err(e) => StepResult::failure(
+                &self.scenario,
+                &step.name,
+                start.elapsed(),
+                format!("Request failed: {e}"),
+            ),
+        };
+        Ok(result)
+    }
+}
+
+fn check_assertions(assertions: &Assertions, response: &HttpResponse) -> Option<String> {
+    // Check status code
+    if let Some(expected_status) = assertions.status {
+        if response.status != expected_status {
+            return Some(format!("Expected status {}, got {}", expected_status, response.status));
+        }
+    }
+
+    // Check max response time
+    if let Some(ref max_time) = assertions.max_response_time {
+        if response.duration > max_time.as_duration() {
+            return Some(format!(
+                "Response time {}ms exceeded max {}ms",
+                response.duration.as_millis(),
+                max_time.as_duration().as_millis()
+            ));
+        }
+    }
+
+    // Check body contains
+    if let Some(expected_text) = &assertions.body_contains {
+        if !response.body.contains(expected_text) {
+            return Some(format!("Response body does not contain '{expected_text}'"));
+        }
+    }
+
+    None
+}
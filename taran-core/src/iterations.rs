@@ -0,0 +1,141 @@
+use crate::control::RunContext;
+use crate::plan::ScenarioPlan;
+use crate::vu::VirtualUser;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use taran_config::LoadProfile as LoadProfileConfig;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+/// How the iteration budget is split between VUs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IterationMode {
+    /// Every VU runs exactly this many iterations
+    PerVu(u64),
+    /// All VUs draw from one pool of this many iterations
+    Shared(u64),
+}
+
+impl IterationMode {
+    /// Total number of iterations the run will execute when not cut short,
+    /// saturating at `u64::MAX`
+    pub const fn total(self, vus: usize) -> u64 {
+        match self {
+            Self::PerVu(iterations) => iterations.saturating_mul(vus as u64),
+            Self::Shared(iterations) => iterations,
+        }
+    }
+}
+
+/// Executor that ends after a fixed number of iterations rather than after
+/// a fixed time, with an optional `max_duration` safety cap.
+pub struct IterationsExecutor {
+    vus: usize,
+    mode: IterationMode,
+    max_duration: Option<Duration>,
+}
+
+impl IterationsExecutor {
+    pub const fn new(vus: usize, mode: IterationMode, max_duration: Option<Duration>) -> Self {
+        Self { vus, mode, max_duration }
+    }
+
+    /// Build the executor for iteration-based `[load_profile]` types
+    pub fn from_config(config: &LoadProfileConfig) -> Option<Self> {
+        let (vus, mode, max_duration) = match config {
+            LoadProfileConfig::PerVuIterations { vus, iterations, max_duration } => {
+                (*vus, IterationMode::PerVu(*iterations), max_duration)
+            }
+            LoadProfileConfig::SharedIterations { vus, iterations, max_duration } => {
+                (*vus, IterationMode::Shared(*iterations), max_duration)
+            }
+            _ => return None,
+        };
+        Some(Self::new(vus, mode, max_duration.map(|d| d.as_duration())))
+    }
+
+    /// Run until the iteration budget is used up or `max_duration` elapses
+    pub async fn run(self, plan: Arc<ScenarioPlan>, run: RunContext) {
+        let stop = Arc::new(AtomicBool::new(false));
+        let pool = Arc::new(AtomicU64::new(0));
+        let mut vus = JoinSet::new();
+
+        for id in 0..self.vus {
+            let vu = VirtualUser::new(id, Arc::clone(&plan), run.clone());
+            let stop = Arc::clone(&stop);
+            match self.mode {
+                IterationMode::PerVu(iterations) => {
+                    let mut remaining = iterations;
+                    vus.spawn(vu.run_while(stop, move || {
+                        let granted = remaining > 0;
+                        remaining = remaining.saturating_sub(1);
+                        granted
+                    }));
+                }
+                IterationMode::Shared(iterations) => {
+                    let pool = Arc::clone(&pool);
+                    vus.spawn(vu.run_while(stop, move || {
+                        pool.fetch_add(1, Ordering::Relaxed) < iterations
+                    }));
+                }
+            }
+        }
+
+        let mut deadline = self.max_duration.map(|d| Instant::now() + d);
+        loop {
+            let joined = match deadline {
+                Some(at) => {
+                    if let Ok(joined) = tokio::time::timeout_at(at, vus.join_next()).await {
+                        joined
+                    } else {
+                        warn!(
+                            "max_duration reached before {} iterations completed, stopping VUs",
+                            self.mode.total(self.vus)
+                        );
+                        stop.store(true, Ordering::Relaxed);
+                        deadline = None;
+                        continue;
+                    }
+                }
+                None => vus.join_next().await,
+            };
+            match joined {
+                Some(Ok(())) => {}
+                Some(Err(e)) => warn!("VU task failed: {e}"),
+                None => break,
+            }
+        }
+
+        info!("Iteration budget finished on {} VUs", self.vus);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use taran_config::HumanDuration;
+
+    #[test]
+    fn test_iteration_totals() {
+        assert_eq!(IterationMode::PerVu(10).total(5), 50);
+        assert_eq!(IterationMode::Shared(10).total(5), 10);
+        assert_eq!(IterationMode::PerVu(u64::MAX / 2).total(2), u64::MAX - 1);
+        assert_eq!(IterationMode::PerVu(u64::MAX / 2 + 1).total(2), u64::MAX);
+        assert_eq!(IterationMode::PerVu(u64::MAX).total(usize::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = LoadProfileConfig::PerVuIterations {
+            vus: 3,
+            iterations: 7,
+            max_duration: Some(HumanDuration(Duration::from_secs(30))),
+        };
+        let executor = IterationsExecutor::from_config(&config);
+        assert!(executor.is_some_and(|e| e.vus == 3
+            && e.mode == IterationMode::PerVu(7)
+            && e.max_duration == Some(Duration::from_secs(30))));
+    }
+}
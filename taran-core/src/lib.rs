@@ -1,11 +1,31 @@
+pub mod arrival;
+pub mod client;
+pub mod control;
 pub mod error;
+pub mod executor;
+pub mod extract;
+pub mod feeder;
+pub mod http;
+pub mod iterations;
+pub mod metrics;
 pub mod model;
+pub mod plan;
+pub mod profile;
+pub mod registry;
 pub mod runner;
+pub mod scheduler;
+pub mod session;
+pub mod template;
+pub mod threshold;
 pub mod traits;
+pub mod vu;
 pub mod protocols {
     pub use taran_protocols::error::ProtocolError;
 }
 
+pub use control::{RunContext, StopSignal};
 pub use error::{CoreError, Result};
 pub use model::{Iteration, StepResult, VirtualUserContext, VirtualUserId};
+pub use registry::{ProtocolFactory, ProtocolRegistry, StepSpec};
+pub use session::Session;
 pub use traits::{LoadProfile, MetricsCollector, MetricsSnapshot, Protocol};
@@ -0,0 +1,78 @@
+use crate::model::StepResult;
+use crate::traits::{MetricsCollector, MetricsSnapshot};
+use taran_metrics::{HdrCollector, Timing};
+
+impl MetricsCollector for HdrCollector {
+    fn record(&self, result: &StepResult) {
+        let timing = Timing {
+            service: result.duration,
+            response: result.response_time(),
+            expected_interval: result.expected_interval,
+        };
+        if result.success {
+            self.record_success(
+                &result.scenario,
+                &result.step_name,
+                timing,
+                result.bytes_sent,
+                result.bytes_received,
+            );
+        } else {
+            self.record_failure(
+                &result.scenario,
+                &result.step_name,
+                result.error.as_deref().unwrap_or("Unknown error"),
+                timing,
+            );
+        }
+    }
+
+    fn snapshot(&self) -> MetricsSnapshot {
+        let counters = self.counters();
+        MetricsSnapshot {
+            total_requests: counters.total_requests,
+            successful_requests: counters.successful_requests,
+            failed_requests: counters.failed_requests,
+            total_duration_ms: counters.total_latency_us / 1000,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_record_step_results() {
+        let collector = HdrCollector::new().unwrap();
+        let ok = StepResult {
+            scenario: "default".to_string(),
+            step_name: "GET".to_string(),
+            success: true,
+            duration: Duration::from_millis(20),
+            error: None,
+            status_code: Some(200),
+            bytes_sent: 10,
+            bytes_received: 20,
+            started_at: None,
+            scheduled_at: None,
+            expected_interval: None,
+        };
+        collector.record(&ok);
+        collector.record(&StepResult::failure(
+            "default",
+            "GET",
+            Duration::from_millis(5),
+            "boom".to_string(),
+        ));
+
+        let snapshot = collector.snapshot();
+        assert_eq!(snapshot.total_requests, 2);
+        assert_eq!(snapshot.successful_requests, 1);
+        assert_eq!(snapshot.failed_requests, 1);
+        assert_eq!(snapshot.total_duration_ms, 25);
+        assert_eq!(collector.summary().errors_by_type["boom"], 1);
+    }
+}
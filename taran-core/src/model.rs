@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Unique identifier for a Virtual User
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -12,6 +12,8 @@ pub struct Iteration(pub u64);
 /// Result of a single request/step execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StepResult {
+    /// Name of the scenario the step belongs to
+    pub scenario: String,
     pub step_name: String,
     pub success: bool,
     pub duration: Duration,
@@ -19,6 +21,48 @@ pub struct StepResult {
     pub status_code: Option<u16>,
     pub bytes_sent: u64,
     pub bytes_received: u64,
+    /// When the step actually started; set by the VU
+    #[serde(skip)]
+    pub started_at: Option<Instant>,
+    /// When the step was due to start. Earlier than `started_at` when the
+    /// load generator fell behind, e.g. an open-loop iteration waiting for
+    /// a free VU.
+    #[serde(skip)]
+    pub scheduled_at: Option<Instant>,
+    /// Closed-loop pace used to back-fill the response time, from
+    /// `[metrics] expected_interval`
+    #[serde(skip)]
+    pub expected_interval: Option<Duration>,
+}
+
+impl StepResult {
+    /// Result of a step that failed before a response was received
+    pub fn failure(scenario: &str, step_name: &str, duration: Duration, error: String) -> Self {
+        Self {
+            scenario: scenario.to_string(),
+            step_name: step_name.to_string(),
+            success: false,
+            duration,
+            error: Some(error),
+            status_code: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            started_at: None,
+            scheduled_at: None,
+            expected_interval: None,
+        }
+    }
+
+    /// Scheduled start to completion; the same as `duration` when the step
+    /// started on time
+    pub fn response_time(&self) -> Duration {
+        match (self.scheduled_at, self.started_at) {
+            (Some(scheduled), Some(started)) => {
+                self.duration + started.saturating_duration_since(scheduled)
+            }
+            _ => self.duration,
+        }
+    }
 }
 
 /// Context shared across a VU's execution
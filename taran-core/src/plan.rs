@@ -0,0 +1,44 @@
+use crate::client::ClientPool;
+use crate::error::Result;
+use crate::feeder::DataFeeder;
+use crate::registry::{ProtocolRegistry, StepSpec};
+use crate::traits::Protocol;
+use std::sync::Arc;
+use taran_config::{NamedScenario, Step};
+
+/// Everything a VU needs to execute one named scenario, prepared once at
+/// load time and shared read-only between all VUs of that scenario
+#[derive(Debug)]
+pub struct ScenarioPlan {
+    /// Scenario name, used to tag every recorded metric
+    pub name: String,
+    pub steps: Vec<PlannedStep>,
+    /// Data feeders whose rows are loaded into VU variables each iteration
+    pub feeders: Vec<Arc<DataFeeder>>,
+}
+
+/// A step together with the protocol implementation that executes it
+#[derive(Debug)]
+pub struct PlannedStep {
+    pub config: Step,
+    pub protocol: Box<dyn Protocol>,
+}
+
+impl ScenarioPlan {
+    pub fn new(
+        scenario: &NamedScenario,
+        feeders: &[Arc<DataFeeder>],
+        registry: &ProtocolRegistry,
+    ) -> Result<Self> {
+        let clients = ClientPool::new(scenario.http.unwrap_or_default().client)?;
+        let steps = scenario
+            .steps
+            .iter()
+            .map(|step| {
+                let spec = StepSpec { scenario: &scenario.name, step, clients: &clients };
+                Ok(PlannedStep { config: step.clone(), protocol: registry.build(&spec)? })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { name: scenario.name.clone(), steps, feeders: feeders.to_vec() })
+    }
+}
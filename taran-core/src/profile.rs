@@ -0,0 +1,248 @@
+use crate::traits::LoadProfile;
+use std::time::Duration;
+use taran_config::LoadProfile as LoadProfileConfig;
+
+/// Fixed number of VUs, optionally reached by a linear ramp-up.
+///
+/// The ramp-up is part of `duration`, not added on top of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstantProfile {
+    pub users: usize,
+    pub duration: Duration,
+    pub ramp_up: Duration,
+}
+
+/// Linear change from `from` to `to` VUs over `duration`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RampProfile {
+    pub from: usize,
+    pub to: usize,
+    pub duration: Duration,
+}
+
+/// Consecutive segments, each holding a fixed number of VUs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SteppedProfile {
+    /// `(users, segment duration)` in execution order
+    pub segments: Vec<(usize, Duration)>,
+}
+
+/// Baseline load with a single burst of `peak` VUs centred in the run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpikeProfile {
+    pub baseline: usize,
+    pub peak: usize,
+    pub spike_duration: Duration,
+    pub total_duration: Duration,
+}
+
+impl LoadProfile for ConstantProfile {
+    fn virtual_users_at(&self, elapsed: Duration) -> usize {
+        if elapsed >= self.duration {
+            0
+        } else if elapsed < self.ramp_up {
+            interpolate(0, self.users, elapsed, self.ramp_up)
+        } else {
+            self.users
+        }
+    }
+
+    fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl LoadProfile for RampProfile {
+    fn virtual_users_at(&self, elapsed: Duration) -> usize {
+        if elapsed >= self.duration {
+            return 0;
+        }
+        interpolate(self.from, self.to, elapsed, self.duration)
+    }
+
+    fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl LoadProfile for SteppedProfile {
+    fn virtual_users_at(&self, elapsed: Duration) -> usize {
+        let mut segment_end = Duration::ZERO;
+        for &(users, duration) in &self.segments {
+            segment_end += duration;
+            if elapsed < segment_end {
+                return users;
+            }
+        }
+        0
+    }
+
+    fn duration(&self) -> Duration {
+        self.segments.iter().map(|&(_, duration)| duration).sum()
+    }
+}
+
+impl SpikeProfile {
+    /// Offset at which the spike begins
+    pub fn spike_start(&self) -> Duration {
+        self.total_duration.saturating_sub(self.spike_duration) / 2
+    }
+}
+
+impl LoadProfile for SpikeProfile {
+    fn virtual_users_at(&self, elapsed: Duration) -> usize {
+        if elapsed >= self.total_duration {
+            return 0;
+        }
+        let spike_start = self.spike_start();
+        if elapsed >= spike_start && elapsed < spike_start + self.spike_duration {
+            self.peak
+        } else {
+            self.baseline
+        }
+    }
+
+    fn duration(&self) -> Duration {
+        self.total_duration
+    }
+}
+
+/// Build the load profile described by a scenario's `[load_profile]` section.
+///
+/// Returns `None` for open-loop arrival-rate types and iteration-based types,
+/// which are not driven by a VU count over time (see [`crate::arrival`] and
+/// [`crate::iterations`]).
+pub fn from_config(config: &LoadProfileConfig) -> Option<Box<dyn LoadProfile>> {
+    let profile: Box<dyn LoadProfile> = match config {
+        LoadProfileConfig::Constant { users, duration, ramp_up } => Box::new(ConstantProfile {
+            users: *users,
+            duration: duration.as_duration(),
+            ramp_up: ramp_up.map_or(Duration::ZERO, |r| r.as_duration()),
+        }),
+        LoadProfileConfig::Ramp { from, to, duration } => {
+            Box::new(RampProfile { from: *from, to: *to, duration: duration.as_duration() })
+        }
+        LoadProfileConfig::Stepped { steps } => Box::new(SteppedProfile {
+            segments: steps.iter().map(|s| (s.users, s.duration.as_duration())).collect(),
+        }),
+        LoadProfileConfig::Spike { baseline, peak, spike_duration, total_duration } => {
+            Box::new(SpikeProfile {
+                baseline: *baseline,
+                peak: *peak,
+                spike_duration: spike_duration.as_duration(),
+                total_duration: total_duration.as_duration(),
+            })
+        }
+        LoadProfileConfig::ConstantArrivalRate { .. }
+        | LoadProfileConfig::RampingArrivalRate { .. }
+        | LoadProfileConfig::PerVuIterations { .. }
+        | LoadProfileConfig::SharedIterations { .. } => return None,
+    };
+    Some(profile)
+}
+
+/// Linear interpolation between two VU counts, rounded down
+fn interpolate(from: usize, to: usize, elapsed: Duration, over: Duration) -> usize {
+    if over.is_zero() {
+        return to;
+    }
+    let progress = (elapsed.as_secs_f64() / over.as_secs_f64()).min(1.0);
+    let value = (to as f64 - from as f64).mul_add(progress, from as f64);
+    value.floor().max(0.0) as usize
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    #[test]
+    fn test_constant_without_ramp_up() {
+        let profile = ConstantProfile { users: 10, duration: secs(60), ramp_up: Duration::ZERO };
+        assert_eq!(profile.virtual_users_at(Duration::ZERO), 10);
+        assert_eq!(profile.virtual_users_at(secs(59)), 10);
+        assert_eq!(profile.virtual_users_at(secs(60)), 0);
+        assert_eq!(profile.duration(), secs(60));
+    }
+
+    #[test]
+    fn test_constant_with_ramp_up() {
+        let profile = ConstantProfile { users: 10, duration: secs(60), ramp_up: secs(10) };
+        assert_eq!(profile.virtual_users_at(Duration::ZERO), 0);
+        assert_eq!(profile.virtual_users_at(secs(5)), 5);
+        assert_eq!(profile.virtual_users_at(secs(10)), 10);
+        assert_eq!(profile.duration(), secs(60));
+    }
+
+    #[test]
+    fn test_ramp_up_and_down() {
+        let up = RampProfile { from: 0, to: 100, duration: secs(100) };
+        assert_eq!(up.virtual_users_at(Duration::ZERO), 0);
+        assert_eq!(up.virtual_users_at(secs(50)), 50);
+        assert_eq!(up.virtual_users_at(secs(99)), 99);
+        assert_eq!(up.virtual_users_at(secs(100)), 0);
+
+        let down = RampProfile { from: 20, to: 10, duration: secs(10) };
+        assert_eq!(down.virtual_users_at(Duration::ZERO), 20);
+        assert_eq!(down.virtual_users_at(secs(5)), 15);
+    }
+
+    #[test]
+    fn test_stepped_segments() {
+        let profile =
+            SteppedProfile { segments: vec![(5, secs(10)), (10, secs(10)), (20, secs(5))] };
+        assert_eq!(profile.duration(), secs(25));
+        assert_eq!(profile.virtual_users_at(Duration::ZERO), 5);
+        assert_eq!(profile.virtual_users_at(secs(10)), 10);
+        assert_eq!(profile.virtual_users_at(secs(20)), 20);
+        assert_eq!(profile.virtual_users_at(Duration::from_millis(24_999)), 20);
+        assert_eq!(profile.virtual_users_at(secs(25)), 0);
+    }
+
+    #[test]
+    fn test_stepped_empty() {
+        let profile = SteppedProfile { segments: Vec::new() };
+        assert_eq!(profile.duration(), Duration::ZERO);
+        assert_eq!(profile.virtual_users_at(Duration::ZERO), 0);
+    }
+
+    #[test]
+    fn test_spike_edges() {
+        let profile = SpikeProfile {
+            baseline: 10,
+            peak: 100,
+            spike_duration: secs(20),
+            total_duration: secs(60),
+        };
+        assert_eq!(profile.spike_start(), secs(20));
+        assert_eq!(profile.virtual_users_at(Duration::ZERO), 10);
+        assert_eq!(profile.virtual_users_at(Duration::from_millis(19_999)), 10);
+        assert_eq!(profile.virtual_users_at(secs(20)), 100);
+        assert_eq!(profile.virtual_users_at(Duration::from_millis(39_999)), 100);
+        assert_eq!(profile.virtual_users_at(secs(40)), 10);
+        assert_eq!(profile.virtual_users_at(secs(60)), 0);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = LoadProfileConfig::Stepped {
+            steps: vec![
+                taran_config::scenario::LoadStep {
+                    users: 2,
+                    duration: taran_config::HumanDuration(secs(1)),
+                },
+                taran_config::scenario::LoadStep {
+                    users: 4,
+                    duration: taran_config::HumanDuration(secs(2)),
+                },
+            ],
+        };
+        let profile = from_config(&config).unwrap();
+        assert_eq!(profile.duration(), secs(3));
+        assert_eq!(profile.virtual_users_at(secs(2)), 4);
+    }
+}
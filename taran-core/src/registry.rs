@@ -0,0 +1,123 @@
+use crate::client::ClientPool;
+use crate::error::{CoreError, Result};
+use crate::http::HttpProtocol;
+use crate::traits::Protocol;
+use std::collections::HashMap;
+use taran_config::{Scenario, Step};
+
+/// Everything a protocol factory may use to build one step
+pub struct StepSpec<'a> {
+    /// Name of the scenario the step belongs to
+    pub scenario: &'a str,
+    pub step: &'a Step,
+    /// HTTP clients of the scenario
+    pub clients: &'a ClientPool,
+}
+
+/// Builds the protocol implementation for one step
+pub type ProtocolFactory = fn(&StepSpec<'_>) -> Result<Box<dyn Protocol>>;
+
+/// Protocol implementations keyed by the lower-cased `Step.protocol` name.
+///
+/// [`ProtocolRegistry::default`] knows every built-in protocol; more can be
+/// added with [`register`](Self::register) before the runner is created.
+#[derive(Debug, Clone)]
+pub struct ProtocolRegistry {
+    factories: HashMap<String, ProtocolFactory>,
+}
+
+impl Default for ProtocolRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register("http", HttpProtocol::factory);
+        registry
+    }
+}
+
+impl ProtocolRegistry {
+    /// A registry without any protocols
+    pub fn empty() -> Self {
+        Self { factories: HashMap::new() }
+    }
+
+    /// Add or replace the factory for `name`
+    pub fn register(&mut self, name: &str, factory: ProtocolFactory) {
+        self.factories.insert(name.to_lowercase(), factory);
+    }
+
+    /// Registered protocol names, sorted
+    pub fn protocols(&self) -> Vec<&str> {
+        let mut names: Vec<_> = self.factories.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Build the protocol for a step
+    pub fn build(&self, spec: &StepSpec<'_>) -> Result<Box<dyn Protocol>> {
+        let factory = self.factory(spec.step)?;
+        factory(spec)
+    }
+
+    /// Reject steps whose protocol is not registered
+    pub fn check(&self, scenario: &Scenario) -> Result<()> {
+        for named in scenario.named_scenarios() {
+            for step in &named.steps {
+                self.factory(step)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn factory(&self, step: &Step) -> Result<ProtocolFactory> {
+        self.factories.get(&step.protocol.to_lowercase()).copied().ok_or_else(|| {
+            CoreError::UnknownProtocol {
+                step: step.name.clone(),
+                protocol: step.protocol.clone(),
+                supported: self.protocols().join(", "),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+[scenario]
+name = "Protocols"
+
+[load_profile]
+type = "constant"
+users = 1
+duration = "1s"
+
+[[steps]]
+name = "Socket"
+protocol = "websocket"
+method = "GET"
+url = "ws://localhost/"
+"#;
+
+    #[test]
+    fn test_unknown_protocol_is_rejected() {
+        let scenario = Scenario::from_toml(SCENARIO).unwrap();
+        let err = ProtocolRegistry::default().check(&scenario).unwrap_err().to_string();
+        assert!(err.contains("'websocket'"), "{err}");
+        assert!(err.contains("supported: http"), "{err}");
+
+        let http = Scenario::from_toml(&SCENARIO.replace("\"websocket\"", "\"HTTP\"")).unwrap();
+        assert!(ProtocolRegistry::default().check(&http).is_ok());
+    }
+
+    #[test]
+    fn test_register_protocol() {
+        let mut registry = ProtocolRegistry::empty();
+        assert!(registry.protocols().is_empty());
+        registry.register("WebSocket", HttpProtocol::factory);
+        assert_eq!(registry.protocols(), ["websocket"]);
+        let scenario = Scenario::from_toml(SCENARIO).unwrap();
+        assert!(registry.check(&scenario).is_ok());
+    }
+}
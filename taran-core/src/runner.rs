@@ -1,178 +1,149 @@
-use crate::error::Result;
-use crate::model::{StepResult, VirtualUserContext};
+use crate::control::{RunContext, StopSignal};
+use crate::error::{CoreError, Result};
+use crate::executor::Executor;
+use crate::feeder::DataFeeder;
+use crate::plan::ScenarioPlan;
+use crate::registry::ProtocolRegistry;
+use crate::threshold;
 use std::sync::Arc;
 use std::time::Duration;
-use taran_config::{Assertions, Scenario};
-use taran_metrics::{MetricsSummary, SimpleCollector};
-use taran_protocols::{HttpClient, HttpRequest};
-use tracing::{debug, info, warn};
+use taran_config::{Scenario, ThresholdSettings};
+use taran_metrics::{HdrCollector, MetricsSummary, ThresholdOutcome};
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tracing::{info, warn};
 
-/// Test runner - Phase 0 implementation with 1 VU, sequential execution
+/// Test runner - runs every named scenario with its own executor
 pub struct TestRunner {
     scenario: Scenario,
-    collector: Arc<SimpleCollector>,
+    registry: ProtocolRegistry,
 }
 
 impl TestRunner {
+    /// Runner with the built-in protocols
     pub fn new(scenario: Scenario) -> Self {
-        Self { scenario, collector: Arc::new(SimpleCollector::new()) }
+        Self::with_registry(scenario, ProtocolRegistry::default())
+    }
+
+    /// Runner resolving step protocols through a custom registry
+    pub const fn with_registry(scenario: Scenario, registry: ProtocolRegistry) -> Self {
+        Self { scenario, registry }
     }
 
     /// Run the load test
     pub async fn run(&self) -> Result<MetricsSummary> {
         info!("Starting test: {}", self.scenario.scenario.name);
 
-        // Phase 0: Simple implementation with 1 VU
-        // TODO: In Phase 1, this will spawn multiple VUs based on load_profile
-
-        let iterations = 10; // Hard-coded for Phase 0
-        let mut context = VirtualUserContext::new(0);
-
-        for i in 0..iterations {
-            context.iteration = i;
-            info!("VU 0, iteration {i}");
-
-            for step in &self.scenario.steps {
-                let result = self.execute_step(step, &mut context).await;
-                self.record_result(&result);
-            }
-
-            // Small delay between iterations
-            tokio::time::sleep(Duration::from_millis(100)).await;
+        // Build every feeder, executor and plan up front so bad config fails
+        // before any load starts
+        let base_dir = self.scenario.base_dir.as_deref();
+        let feeders = self
+            .scenario
+            .data
+            .iter()
+            .map(|source| DataFeeder::load(source, base_dir).map(Arc::new))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut workloads = Vec::new();
+        for named in self.scenario.named_scenarios() {
+            let executor = Executor::from_config(&named.load_profile)?;
+            let plan = Arc::new(ScenarioPlan::new(&named, &feeders, &self.registry)?);
+            workloads.push((named.start_offset(), plan, executor));
         }
 
-        info!("Test completed");
-        Ok(self.collector.summary())
-    }
-
-    async fn execute_step(
-        &self,
-        step: &taran_config::Step,
-        _context: &mut VirtualUserContext,
-    ) -> StepResult {
-        debug!("Executing step: {}", step.name);
-
-        let start = std::time::Instant::now();
-
-        // Only HTTP is supported in Phase 0
-        if step.protocol.to_lowercase() != "http" {
-            warn!("Unsupported protocol: {}", step.protocol);
-            return StepResult {
-                step_name: step.name.clone(),
-                success: false,
-                duration: start.elapsed(),
-                error: Some(format!("Unsupported protocol: {}", step.protocol)),
-                status_code: None,
-                bytes_sent: 0,
-                bytes_received: 0,
-            };
+        let mut context = RunContext::new(HdrCollector::new()?);
+        context.expected_interval =
+            self.scenario.metrics.expected_interval.map(|d| d.as_duration());
+        let finished = StopSignal::new();
+        let sampler = tokio::spawn(sample(
+            context.clone(),
+            self.scenario.metrics.snapshot_interval(),
+            self.scenario.thresholds.clone(),
+            finished.clone(),
+        ));
+
+        let mut running = JoinSet::new();
+        for (start_after, plan, executor) in workloads {
+            let context = context.clone();
+            running.spawn(async move {
+                if !start_after.is_zero() {
+                    info!("Scenario '{}' starts in {:?}", plan.name, start_after);
+                    tokio::select! {
+                        () = tokio::time::sleep(start_after) => {}
+                        () = context.stop.stopped() => return,
+                    }
+                }
+                info!("Starting scenario '{}'", plan.name);
+                let name = plan.name.clone();
+                executor.run(plan, context).await;
+                info!("Scenario '{name}' completed");
+            });
         }
 
-        let client = match HttpClient::new() {
-            Ok(c) => c,
-            Err(e) => {
-                return StepResult {
-                    step_name: step.name.clone(),
-                    success: false,
-                    duration: start.elapsed(),
-                    error: Some(format!("Failed to create HTTP client: {e}")),
-                    status_code: None,
-                    bytes_sent: 0,
-                    bytes_received: 0,
-                };
-            }
-        };
-
-        let request = HttpRequest {
-            method: step.method.clone(),
-            url: step.url.clone(),
-            headers: step.headers.clone(),
-            body: step.body.clone(),
-            timeout: None,
-        };
-
-        match client.execute(request).await {
-            Ok(response) => {
-                let success = response.is_success();
-                let duration = response.duration;
-
-                // Check assertions if present
-                let assertion_error = step
-                    .assertions
-                    .as_ref()
-                    .and_then(|assertions| check_assertions(assertions, &response));
-
-                // TODO: Extract variables if extractors are defined
-                // This will be implemented in Phase 1
+        let joined = join_all(&mut running).await;
+        finished.stop();
+        let breach = sampler.await;
+        joined?;
+        let breach = breach
+            .map_err(|e| CoreError::ExecutionFailed(format!("Metrics sampler failed: {e}")))?;
 
-                StepResult {
-                    step_name: step.name.clone(),
-                    success: success && assertion_error.is_none(),
-                    duration,
-                    error: assertion_error,
-                    status_code: Some(response.status),
-                    bytes_sent: response.bytes_sent,
-                    bytes_received: response.bytes_received,
-                }
+        info!("Test completed");
+        let mut summary = context.collector.summary();
+        summary.thresholds = threshold::evaluate(&self.scenario.thresholds.rules, &summary);
+        if let Some(breach) = breach {
+            let aborted = summary
+                .thresholds
+                .iter_mut()
+                .find(|outcome| outcome.expression == breach.expression);
+            if let Some(outcome) = aborted {
+                *outcome = breach;
             }
-            Err(e) => StepResult {
-                step_name: step.name.clone(),
-                success: false,
-                duration: start.elapsed(),
-                error: Some(format!("Request failed: {e}")),
-                status_code: None,
-                bytes_sent: 0,
-                bytes_received: 0,
-            },
         }
+        Ok(summary)
     }
+}
 
-    fn record_result(&self, result: &StepResult) {
-        if result.success {
-            self.collector.record_success(
-                &result.step_name,
-                result.duration,
-                result.bytes_sent,
-                result.bytes_received,
-            );
-        } else {
-            self.collector.record_failure(
-                &result.step_name,
-                result.error.as_deref().unwrap_or("Unknown error"),
-                result.duration,
-            );
-        }
+async fn join_all(running: &mut JoinSet<()>) -> Result<()> {
+    while let Some(joined) = running.join_next().await {
+        joined.map_err(|e| CoreError::ExecutionFailed(format!("Scenario task failed: {e}")))?;
     }
+    Ok(())
 }
 
-fn check_assertions(
-    assertions: &Assertions,
-    response: &taran_protocols::HttpResponse,
-) -> Option<String> {
-    // Check status code
-    if let Some(expected_status) = assertions.status {
-        if response.status != expected_status {
-            return Some(format!("Expected status {}, got {}", expected_status, response.status));
-        }
+/// Record a time-series window every `interval` until `finished` is raised,
+/// then the last partial one.
+///
+/// After each window the `abort_on_fail` thresholds are checked against the
+/// rolling `abort_window` ending with it, so one noisy window cannot abort
+/// the run on its own; the first rule to fail stops the run and is returned.
+async fn sample(
+    run: RunContext,
+    interval: Duration,
+    thresholds: ThresholdSettings,
+    finished: StopSignal,
+) -> Option<ThresholdOutcome> {
+    let aborting = thresholds.rules.iter().any(|threshold| threshold.abort_on_fail);
+    if aborting {
+        let windows = thresholds.abort_window().as_nanos().div_ceil(interval.as_nanos().max(1));
+        run.collector.keep_recent_windows(usize::try_from(windows).unwrap_or(usize::MAX).max(1));
     }
-
-    // Check max response time
-    if let Some(ref max_time) = assertions.max_response_time {
-        if response.duration > max_time.as_duration() {
-            return Some(format!(
-                "Response time {}ms exceeded max {}ms",
-                response.duration.as_millis(),
-                max_time.as_duration().as_millis()
-            ));
+    let mut ticker = tokio::time::interval_at(Instant::now() + interval, interval);
+    let mut breach = None;
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            () = finished.stopped() => break,
         }
-    }
-
-    // Check body contains
-    if let Some(expected_text) = &assertions.body_contains {
-        if !response.body.contains(expected_text) {
-            return Some(format!("Response body does not contain '{expected_text}'"));
+        run.collector.record_interval();
+        if aborting && breach.is_none() {
+            breach = threshold::check_abort(&thresholds.rules, &run.collector.recent());
+            if let Some(outcome) = &breach {
+                warn!("Threshold '{}' failed, stopping the test", outcome.expression);
+                run.stop.stop();
+            }
         }
     }
-
-    None
+    // Keep the last partial window
+    run.collector.record_interval();
+    breach
 }
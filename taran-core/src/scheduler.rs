@@ -0,0 +1,98 @@
+use crate::control::StopSignal;
+use crate::traits::LoadProfile;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, MissedTickBehavior};
+use tracing::{debug, info, warn};
+
+/// How often the scheduler re-evaluates the target VU count
+const SCHEDULER_TICK: Duration = Duration::from_millis(50);
+
+/// A running VU task together with its stop signal
+struct ActiveVu {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
+}
+
+/// Spawns and retires VU tasks so that the live VU count follows a load profile.
+///
+/// `spawn_vu` receives the id of the new VU and its stop signal and returns
+/// the future driving that VU. VUs are retired newest-first; a retired VU
+/// finishes its in-flight request before exiting.
+pub struct VuScheduler<F> {
+    spawn_vu: F,
+    active: Vec<ActiveVu>,
+    retired: Vec<JoinHandle<()>>,
+    next_id: usize,
+}
+
+impl<F, Fut> VuScheduler<F>
+where
+    F: FnMut(usize, Arc<AtomicBool>) -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    pub const fn new(spawn_vu: F) -> Self {
+        Self { spawn_vu, active: Vec::new(), retired: Vec::new(), next_id: 0 }
+    }
+
+    /// Drive the profile until its duration elapses or the test is
+    /// stopped, then stop every VU and wait for all of them to finish.
+    pub async fn run(mut self, profile: &dyn LoadProfile, test_stop: &StopSignal) {
+        let total = profile.duration();
+        let start = Instant::now();
+        let mut ticker = tokio::time::interval(SCHEDULER_TICK);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+            let elapsed = start.elapsed();
+            if elapsed >= total {
+                break;
+            }
+            if test_stop.is_stopped() {
+                info!("Test stopped after {elapsed:?}");
+                break;
+            }
+            self.scale_to(profile.virtual_users_at(elapsed));
+        }
+
+        info!("Load profile finished after {:?}, stopping {} VUs", total, self.active.len());
+        self.scale_to(0);
+        for handle in self.retired {
+            if let Err(e) = handle.await {
+                warn!("VU task failed: {e}");
+            }
+        }
+    }
+
+    /// Number of VUs currently running
+    pub fn active_count(&self) -> usize {
+        self.active.len()
+    }
+
+    fn scale_to(&mut self, target: usize) {
+        // VU tasks that ended on their own no longer count towards the target
+        self.active.retain(|vu| !vu.handle.is_finished());
+        self.retired.retain(|handle| !handle.is_finished());
+
+        let current = self.active.len();
+        if target > current {
+            debug!("Scaling up: {current} -> {target} VUs");
+            for _ in current..target {
+                let stop = Arc::new(AtomicBool::new(false));
+                let handle = tokio::spawn((self.spawn_vu)(self.next_id, Arc::clone(&stop)));
+                self.next_id += 1;
+                self.active.push(ActiveVu { stop, handle });
+            }
+        } else if target < current {
+            debug!("Scaling down: {current} -> {target} VUs");
+            for vu in self.active.drain(target..) {
+                vu.stop.store(true, Ordering::Relaxed);
+                self.retired.push(vu.handle);
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// Per-VU protocol state kept across steps and iterations, such as an HTTP
+/// client or an open socket, stored by type
+#[derive(Debug, Default)]
+pub struct Session {
+    state: HashMap<TypeId, Box<dyn Any + Send>>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get<T: Any + Send>(&self) -> Option<&T> {
+        self.state.get(&TypeId::of::<T>()).and_then(|value| value.downcast_ref())
+    }
+
+    pub fn get_mut<T: Any + Send>(&mut self) -> Option<&mut T> {
+        self.state.get_mut(&TypeId::of::<T>()).and_then(|value| value.downcast_mut())
+    }
+
+    /// Store a value, replacing any previous value of the same type
+    pub fn insert<T: Any + Send>(&mut self, value: T) {
+        self.state.insert(TypeId::of::<T>(), Box::new(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_are_keyed_by_type() {
+        let mut session = Session::new();
+        assert!(session.get::<String>().is_none());
+        session.insert("conn".to_string());
+        session.insert(7u32);
+        assert_eq!(session.get::<String>().map(String::as_str), Some("conn"));
+        if let Some(count) = session.get_mut::<u32>() {
+            *count += 1;
+        }
+        assert_eq!(session.get::<u32>(), Some(&8));
+    }
+}
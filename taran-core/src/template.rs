@@ -0,0 +1,79 @@
+use crate::model::VirtualUserContext;
+use std::time::{SystemTime, UNIX_EPOCH};
+use taran_config::{Builtin, Segment, Template};
+
+/// Render a pre-parsed template against a VU's variables.
+///
+/// Fails when a referenced variable has not been set yet, e.g. because an
+/// optional extractor in an earlier step matched nothing.
+pub fn render(template: &Template, context: &VirtualUserContext) -> Result<String, String> {
+    let mut out = String::new();
+    for segment in template.segments() {
+        match segment {
+            Segment::Literal(text) => out.push_str(text),
+            Segment::Variable(name) => {
+                let value = context
+                    .get_variable(name)
+                    .ok_or_else(|| format!("variable '{name}' is not set"))?;
+                out.push_str(value);
+            }
+            Segment::Builtin(builtin) => out.push_str(&render_builtin(*builtin, context)),
+        }
+    }
+    Ok(out)
+}
+
+fn render_builtin(builtin: Builtin, context: &VirtualUserContext) -> String {
+    match builtin {
+        Builtin::Vu => context.id.to_string(),
+        Builtin::Iteration => context.iteration.to_string(),
+        Builtin::Uuid => uuid::Uuid::new_v4().to_string(),
+        Builtin::Timestamp => {
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis()).to_string()
+        }
+        Builtin::RandomInt(min, max) => fastrand::i64(min..=max).to_string(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_variables_and_builtins() {
+        let mut context = VirtualUserContext::new(3);
+        context.iteration = 7;
+        context.set_variable("token".to_string(), "abc".to_string());
+
+        let template = Template::parse("Bearer {{token}} vu={{__vu}} iter={{__iter}}").unwrap();
+        assert_eq!(render(&template, &context).unwrap(), "Bearer abc vu=3 iter=7");
+    }
+
+    #[test]
+    fn test_render_random_builtins() {
+        let context = VirtualUserContext::new(0);
+
+        let n: i64 = render(&Template::parse("{{__random_int(5,6)}}").unwrap(), &context)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((5..=6).contains(&n));
+
+        let id = render(&Template::parse("{{__uuid}}").unwrap(), &context).unwrap();
+        assert!(uuid::Uuid::parse_str(&id).is_ok());
+
+        let ts: u128 = render(&Template::parse("{{__timestamp}}").unwrap(), &context)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(ts > 0);
+    }
+
+    #[test]
+    fn test_render_missing_variable() {
+        let context = VirtualUserContext::new(0);
+        let template = Template::parse("/items/{{item_id}}").unwrap();
+        assert!(render(&template, &context).unwrap_err().contains("item_id"));
+    }
+}
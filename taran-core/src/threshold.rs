@@ -0,0 +1,267 @@
+use taran_config::{LatencyStat, Threshold, ThresholdMetric};
+use taran_metrics::{
+    IntervalSnapshot, LatencyDistribution, MetricsSummary, StepSummary, ThresholdOutcome,
+};
+
+/// The figures a threshold can be checked against: a whole run or one
+/// time-series window
+struct Observed<'a> {
+    response_time: &'a LatencyDistribution,
+    service_time: &'a LatencyDistribution,
+    total_requests: u64,
+    failed_requests: u64,
+    requests_per_sec: f64,
+    steps: &'a [StepSummary],
+}
+
+impl<'a> From<&'a MetricsSummary> for Observed<'a> {
+    fn from(summary: &'a MetricsSummary) -> Self {
+        Self {
+            response_time: &summary.response_time,
+            service_time: &summary.service_time,
+            total_requests: summary.total_requests,
+            failed_requests: summary.failed_requests,
+            requests_per_sec: summary.requests_per_sec,
+            steps: &summary.steps,
+        }
+    }
+}
+
+impl<'a> From<&'a IntervalSnapshot> for Observed<'a> {
+    fn from(snapshot: &'a IntervalSnapshot) -> Self {
+        Self {
+            response_time: &snapshot.response_time,
+            service_time: &snapshot.service_time,
+            total_requests: snapshot.total_requests,
+            failed_requests: snapshot.failed_requests,
+            requests_per_sec: snapshot.requests_per_sec,
+            steps: &snapshot.steps,
+        }
+    }
+}
+
+/// Evaluate every threshold against the summary of a finished run.
+///
+/// A step-scoped rule matching several steps (the same step name in more
+/// than one scenario) must hold for each of them and reports the first
+/// failing value. A step that never ran fails its rules.
+pub fn evaluate(thresholds: &[Threshold], summary: &MetricsSummary) -> Vec<ThresholdOutcome> {
+    let observed = Observed::from(summary);
+    thresholds.iter().map(|threshold| evaluate_one(threshold, &observed)).collect()
+}
+
+/// Check the `abort_on_fail` rules against a rolling window of recent
+/// metrics, see [`HdrCollector::recent`](taran_metrics::HdrCollector::recent),
+/// and return the first that failed.
+///
+/// Rules still in their `delay_abort_eval` grace period are skipped, as
+/// are rules whose metric had no requests in the window.
+pub fn check_abort(
+    thresholds: &[Threshold],
+    window: &IntervalSnapshot,
+) -> Option<ThresholdOutcome> {
+    let observed = Observed::from(window);
+    thresholds
+        .iter()
+        .filter(|threshold| threshold.abort_on_fail)
+        .filter(|threshold| window.elapsed_secs >= threshold.abort_delay().as_secs_f64())
+        .map(|threshold| evaluate_one(threshold, &observed))
+        .find(|outcome| !outcome.passed && outcome.actual.is_some())
+        .map(|outcome| ThresholdOutcome { aborted: true, ..outcome })
+}
+
+fn evaluate_one(threshold: &Threshold, observed: &Observed<'_>) -> ThresholdOutcome {
+    let actuals: Vec<f64> = if observed.total_requests == 0 {
+        Vec::new()
+    } else {
+        threshold.step.as_ref().map_or_else(
+            || vec![global_value(threshold.metric, observed)],
+            |target| {
+                observed
+                    .steps
+                    .iter()
+                    .filter(|step| step_matches(step, target))
+                    .map(|step| step_value(threshold.metric, step))
+                    .collect()
+            },
+        )
+    };
+    let failing = actuals
+        .iter()
+        .copied()
+        .find(|&actual| !threshold.comparison.holds(actual, threshold.limit));
+
+    ThresholdOutcome {
+        expression: threshold.expression.clone(),
+        passed: !actuals.is_empty() && failing.is_none(),
+        actual: failing.or_else(|| actuals.first().copied()),
+        unit: threshold.metric.unit().to_string(),
+        aborted: false,
+    }
+}
+
+fn step_matches(step: &StepSummary, target: &str) -> bool {
+    step.step == target
+        || target
+            .split_once('/')
+            .is_some_and(|(scenario, name)| step.scenario == scenario && step.step == name)
+}
+
+fn global_value(metric: ThresholdMetric, observed: &Observed<'_>) -> f64 {
+    match metric {
+        ThresholdMetric::ResponseTime(stat) => stat_value(observed.response_time, stat),
+        ThresholdMetric::ServiceTime(stat) => stat_value(observed.service_time, stat),
+        ThresholdMetric::ErrorRate => error_rate(observed.failed_requests, observed.total_requests),
+        ThresholdMetric::Rps => observed.requests_per_sec,
+        ThresholdMetric::Requests => observed.total_requests as f64,
+    }
+}
+fn step_value(metric: ThresholdMetric, step: &StepSummary) -> f64 {
+    match metric {
+        ThresholdMetric::ResponseTime(stat) => stat_value(&step.response_time, stat),
+        ThresholdMetric::ServiceTime(stat) => stat_value(&step.service_time, stat),
+        ThresholdMetric::ErrorRate => error_rate(step.failed_requests, step.total_requests),
+        ThresholdMetric::Rps => step.requests_per_sec,
+        ThresholdMetric::Requests => step.total_requests as f64,
+    }
+}
+
+const fn stat_value(distribution: &LatencyDistribution, stat: LatencyStat) -> f64 {
+    match stat {
+        LatencyStat::Avg => distribution.avg_ms,
+        LatencyStat::Min => distribution.min_ms,
+        LatencyStat::Max => distribution.max_ms,
+        LatencyStat::P50 => distribution.p50_ms,
+        LatencyStat::P75 => distribution.p75_ms,
+        LatencyStat::P90 => distribution.p90_ms,
+        LatencyStat::P95 => distribution.p95_ms,
+        LatencyStat::P99 => distribution.p99_ms,
+        LatencyStat::P999 => distribution.p999_ms,
+    }
+}
+
+fn error_rate(failed: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        failed as f64 / total as f64 * 100.0
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use taran_metrics::{HdrCollector, Timing};
+
+    fn summary() -> MetricsSummary {
+        let collector = HdrCollector::new().unwrap();
+        for _ in 0..99 {
+            collector.record_success("shop", "Browse", Duration::from_millis(20), 0, 0);
+        }
+        let slow = Timing {
+            service: Duration::from_millis(400),
+            response: Duration::from_millis(1500),
+            expected_interval: None,
+        };
+        collector.record_failure("shop", "Login", "timeout", slow);
+        collector.summary()
+    }
+
+    fn window(elapsed_secs: f64) -> IntervalSnapshot {
+        let collector = HdrCollector::new().unwrap();
+        collector.record_success("shop", "Browse", Duration::from_millis(20), 0, 0);
+        collector.record_failure("shop", "Login", "timeout", Duration::from_millis(900));
+        IntervalSnapshot { elapsed_secs, ..collector.record_interval() }
+    }
+
+    fn outcomes(rules: &[&str]) -> Vec<ThresholdOutcome> {
+        let thresholds: Vec<_> = rules.iter().map(|rule| Threshold::parse(rule).unwrap()).collect();
+        evaluate(&thresholds, &summary())
+    }
+
+    #[test]
+    fn test_global_thresholds() {
+        let results = outcomes(&[
+            "http_req_duration.p95 < 500ms",
+            "error_rate < 1%",
+            "error_rate <= 1%",
+            "requests == 100",
+            "max < 1s",
+            "service_time.max < 1s",
+        ]);
+        let passed: Vec<_> = results.iter().map(|outcome| outcome.passed).collect();
+        assert_eq!(passed, [true, false, true, true, false, true]);
+        assert_eq!(results[1].unit, "%");
+        assert!((results[1].actual.unwrap() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_step_thresholds() {
+        let results = outcomes(&[
+            "\"Login\".p99 < 1s",
+            "\"shop/Browse\".p99 < 1s",
+            "\"Login\".service_time.p99 < 1s",
+            "\"Missing\".p99 < 1s",
+        ]);
+        let passed: Vec<_> = results.iter().map(|outcome| outcome.passed).collect();
+        assert_eq!(passed, [false, true, true, false]);
+        assert!((results[0].actual.unwrap() - 1500.0).abs() < 2.0);
+        assert_eq!(results[3].actual, None);
+    }
+
+    #[test]
+    fn test_check_abort() {
+        let abort = |rule: &str, delay: Option<&str>| {
+            let mut threshold = Threshold::parse(rule).unwrap();
+            threshold.abort_on_fail = true;
+            threshold.delay_abort_eval = delay.map(|d| d.to_string().try_into().unwrap());
+            threshold
+        };
+        let passive = Threshold::parse("error_rate < 1%").unwrap();
+        let rules = [passive, abort("max < 500ms", Some("10s")), abort("error_rate < 10%", None)];
+
+        let breach = check_abort(&rules, &window(1.0)).unwrap();
+        assert_eq!(breach.expression, "error_rate < 10%");
+        assert!(breach.aborted && !breach.passed);
+        assert!((breach.actual.unwrap() - 50.0).abs() < 1e-9);
+
+        // Past its grace period the latency rule is checked first
+        assert_eq!(check_abort(&rules, &window(10.0)).unwrap().expression, "max < 500ms");
+        assert!(check_abort(&rules[..2], &window(5.0)).is_none());
+    }
+
+    #[test]
+    fn test_abort_needs_a_sustained_breach() {
+        let mut rule = Threshold::parse("p95 < 500ms").unwrap();
+        rule.abort_on_fail = true;
+        let rules = [rule];
+        let collector = HdrCollector::new().unwrap();
+        collector.keep_recent_windows(5);
+        let second = |latency_ms: u64, requests: usize| {
+            for _ in 0..requests {
+                collector.record_success("shop", "Browse", Duration::from_millis(latency_ms), 0, 0);
+            }
+            let window = collector.record_interval();
+            (window, collector.recent())
+        };
+
+        for _ in 0..4 {
+            second(20, 50);
+        }
+        // A single slow request in a quiet second breaches on its own...
+        let (noisy, recent) = second(900, 1);
+        assert!(check_abort(&rules, &noisy).is_some());
+        // ...but not the rolling window around it
+        assert!(check_abort(&rules, &recent).is_none());
+
+        let mut breach = None;
+        for _ in 0..5 {
+            breach = breach.or_else(|| check_abort(&rules, &second(900, 50).1));
+        }
+        let breach = breach.unwrap();
+        assert!(breach.aborted);
+        assert!(breach.actual.unwrap() > 500.0);
+    }
+}
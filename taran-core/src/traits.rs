@@ -1,11 +1,18 @@
-use crate::{error::Result, model::StepResult};
+use crate::{error::Result, model::StepResult, session::Session};
 use async_trait::async_trait;
 
-/// Trait for protocol client implementations
+/// Trait for protocol client implementations.
+///
+/// One instance is built per step at scenario load and shared by every VU;
+/// per-VU state such as connections lives in the VU's [`Session`].
 #[async_trait]
-pub trait Protocol: Send + Sync {
+pub trait Protocol: Send + Sync + std::fmt::Debug {
     /// Execute a request and return the result
-    async fn execute(&self, context: &mut crate::model::VirtualUserContext) -> Result<StepResult>;
+    async fn execute(
+        &self,
+        session: &mut Session,
+        context: &mut crate::model::VirtualUserContext,
+    ) -> Result<StepResult>;
 }
 
 /// Trait for load profile strategies
@@ -0,0 +1,155 @@
+use crate::control::RunContext;
+use crate::model::{StepResult, VirtualUserContext};
+use crate::plan::{PlannedStep, ScenarioPlan};
+use crate::session::Session;
+use crate::traits::MetricsCollector;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use taran_config::OnExhausted;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+/// A single virtual user executing the scenario steps in a loop
+pub struct VirtualUser {
+    context: VirtualUserContext,
+    plan: Arc<ScenarioPlan>,
+    run: RunContext,
+    /// Row held from each feeder, used by `unique_per_vu` sources
+    feeder_rows: Vec<Option<usize>>,
+    /// Connections and other protocol state kept across iterations
+    session: Session,
+}
+
+impl VirtualUser {
+    pub fn new(id: usize, plan: Arc<ScenarioPlan>, run: RunContext) -> Self {
+        let feeder_rows = vec![None; plan.feeders.len()];
+        Self {
+            context: VirtualUserContext::new(id),
+            plan,
+            run,
+            feeder_rows,
+            session: Session::new(),
+        }
+    }
+
+    /// Run iterations until the VU's or the test's stop signal is raised.
+    ///
+    /// The signal is checked before every step, so a retired VU finishes
+    /// its in-flight request but does not start new ones.
+    pub async fn run(self, stop: Arc<AtomicBool>) {
+        self.run_while(stop, || true).await;
+    }
+
+    /// Run iterations while `next_iteration` grants another one, no stop
+    /// signal is raised and every data feeder still has a row.
+    pub async fn run_while<F>(mut self, stop: Arc<AtomicBool>, mut next_iteration: F)
+    where
+        F: FnMut() -> bool + Send,
+    {
+        debug!("VU {} of scenario '{}' started", self.context.id, self.plan.name);
+        let _active = self.run.collector.track_vu();
+
+        while !self.is_stopped(&stop) && next_iteration() {
+            if !self.load_data() {
+                break;
+            }
+            self.run_iteration(&stop).await;
+            self.context.next_iteration();
+
+            // Steps that fail without touching the network never yield,
+            // so give the scheduler a chance to run between iterations
+            tokio::task::yield_now().await;
+        }
+
+        debug!("VU {} stopped after {} iterations", self.context.id, self.context.iteration);
+    }
+
+    /// Run a single open-loop iteration that was due at `scheduled`.
+    ///
+    /// The first step carries `scheduled` as its intended start, so any
+    /// delay before it actually starts (e.g. waiting for a free VU) shows up
+    /// in the response time instead of being hidden. The iteration is
+    /// skipped when a data feeder has run dry, and its remaining steps once
+    /// the test is stopped.
+    pub async fn run_scheduled_iteration(&mut self, scheduled: Instant) {
+        if self.run.stop.is_stopped() || !self.load_data() {
+            return;
+        }
+        let _active = self.run.collector.track_vu();
+        let plan = Arc::clone(&self.plan);
+        let mut scheduled = Some(scheduled);
+        for step in &plan.steps {
+            if self.run.stop.is_stopped() {
+                break;
+            }
+            let result = self.execute(step, scheduled.take()).await;
+            self.run.collector.record(&result);
+        }
+        self.context.next_iteration();
+    }
+
+    async fn run_iteration(&mut self, stop: &AtomicBool) {
+        let plan = Arc::clone(&self.plan);
+        for step in &plan.steps {
+            if self.is_stopped(stop) {
+                return;
+            }
+            let mut result = self.execute(step, None).await;
+            result.expected_interval = self.run.expected_interval;
+            self.run.collector.record(&result);
+        }
+    }
+
+    /// Execute a step through its protocol, stamping when it started and
+    /// when it was due (now, unless `scheduled` says otherwise)
+    async fn execute(&mut self, step: &PlannedStep, scheduled: Option<Instant>) -> StepResult {
+        debug!("Executing step: {}", step.config.name);
+        let started = Instant::now();
+        let mut result = match step.protocol.execute(&mut self.session, &mut self.context).await {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("Step '{}' could not be executed: {e}", step.config.name);
+                StepResult::failure(
+                    &self.plan.name,
+                    &step.config.name,
+                    Duration::ZERO,
+                    e.to_string(),
+                )
+            }
+        };
+        result.started_at = Some(started.into_std());
+        result.scheduled_at = Some(scheduled.unwrap_or(started).into_std());
+        result
+    }
+
+    fn is_stopped(&self, stop: &AtomicBool) -> bool {
+        stop.load(Ordering::Relaxed) || self.run.stop.is_stopped()
+    }
+
+    /// Put the next row of every data feeder into the VU's variables.
+    ///
+    /// Returns `false` when a feeder has no row left for this VU; with
+    /// `on_exhausted = "stop_test"` the whole run is stopped as well.
+    fn load_data(&mut self) -> bool {
+        for (feeder, assigned) in self.plan.feeders.iter().zip(&mut self.feeder_rows) {
+            let Some(index) = feeder.next_row(assigned) else {
+                if feeder.on_exhausted() == OnExhausted::StopTest {
+                    warn!("Data source '{}' exhausted, stopping the test", feeder.name());
+                    self.run.stop.stop();
+                } else {
+                    debug!(
+                        "Data source '{}' exhausted, stopping VU {}",
+                        feeder.name(),
+                        self.context.id
+                    );
+                }
+                return false;
+            };
+            for (column, value) in feeder.row(index) {
+                self.context.set_variable(column.to_string(), value.to_string());
+            }
+        }
+        true
+    }
+}
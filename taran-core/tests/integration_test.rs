@@ -1,9 +1,18 @@
 //! Integration tests for taran-core
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use taran_config::Scenario;
+use taran_core::arrival::{ArrivalRateExecutor, ArrivalRateProfile};
+use taran_core::plan::ScenarioPlan;
 use taran_core::runner::TestRunner;
-use wiremock::matchers::{method, path};
+use taran_core::{
+    Protocol, ProtocolRegistry, RunContext, Session, StepResult, StepSpec, VirtualUserContext,
+};
+use taran_metrics::HdrCollector;
+use wiremock::matchers::{body_string, header, method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[tokio::test]
@@ -113,3 +122,925 @@ body = '{{"key": "value"}}'
     assert!(summary.total_requests >= 2);
     assert_eq!(summary.failed_requests, 0, "Requests failed");
 }
+
+#[tokio::test]
+async fn test_constant_profile_runs_concurrent_vus() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(200)))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Concurrency Test"
+
+[load_profile]
+type = "constant"
+users = 5
+duration = "1s"
+
+[[steps]]
+name = "Slow"
+protocol = "http"
+method = "GET"
+url = "{}/slow"
+"#,
+        mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    let runner = TestRunner::new(scenario);
+
+    let start = Instant::now();
+    let summary = runner.run().await.expect("Test execution failed");
+    let elapsed = start.elapsed();
+
+    // A single VU could complete at most ~5 requests of 200ms in one second
+    assert!(
+        summary.total_requests >= 15,
+        "Expected concurrent VUs, got only {} requests",
+        summary.total_requests
+    );
+    assert_eq!(summary.failed_requests, 0);
+    assert!(elapsed >= Duration::from_secs(1));
+    assert!(elapsed < Duration::from_secs(2), "Run overshot its duration: {elapsed:?}");
+}
+
+#[tokio::test]
+async fn test_ramp_profile_stops_at_duration() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/ramp"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(50)))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Ramp Test"
+
+[load_profile]
+type = "ramp"
+from = 1
+to = 4
+duration = "800ms"
+
+[[steps]]
+name = "Ramp"
+protocol = "http"
+method = "GET"
+url = "{}/ramp"
+"#,
+        mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    let runner = TestRunner::new(scenario);
+
+    let start = Instant::now();
+    let summary = runner.run().await.expect("Test execution failed");
+    let elapsed = start.elapsed();
+
+    assert!(summary.total_requests > 0);
+    assert!(elapsed < Duration::from_millis(1500), "Run overshot its duration: {elapsed:?}");
+}
+
+#[tokio::test]
+async fn test_arrival_rate_measures_from_scheduled_start() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/stall"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(300)))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Open Loop Test"
+
+[load_profile]
+type = "constant_arrival_rate"
+rate = 20
+duration = "1s"
+max_vus = 2
+
+[[steps]]
+name = "Stall"
+protocol = "http"
+method = "GET"
+url = "{}/stall"
+"#,
+        mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    scenario.validate().expect("Scenario validation failed");
+    let runner = TestRunner::new(scenario);
+    let summary = runner.run().await.expect("Test execution failed");
+
+    // Every scheduled iteration runs even though the pool is saturated
+    assert_eq!(summary.total_requests, 20);
+    assert_eq!(summary.failed_requests, 0);
+    // Two VUs serve ~6.7 req/s, so late iterations queue for well over a
+    // second; that wait must show up in the response time rather than be
+    // omitted, while the service time stays that of the server
+    assert!(
+        summary.response_time.max_ms >= 1000.0,
+        "Queueing delay was not counted: max response time {}ms",
+        summary.response_time.max_ms
+    );
+    assert!(summary.service_time.max_ms < 1000.0, "{}", summary.service_time.max_ms);
+    assert!(summary.max_latency_ms < 1000);
+}
+
+#[tokio::test]
+async fn test_per_vu_iterations_runs_exact_count() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/item"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Per-VU Iterations"
+
+[load_profile]
+type = "per_vu_iterations"
+vus = 3
+iterations = 4
+
+[[steps]]
+name = "Item"
+protocol = "http"
+method = "GET"
+url = "{}/item"
+"#,
+        mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    let runner = TestRunner::new(scenario);
+    let summary = runner.run().await.expect("Test execution failed");
+
+    assert_eq!(summary.total_requests, 12);
+    assert_eq!(summary.failed_requests, 0);
+}
+
+#[tokio::test]
+async fn test_shared_iterations_respects_max_duration() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(100)))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Shared Iterations"
+
+[load_profile]
+type = "shared_iterations"
+vus = 2
+iterations = 1000
+max_duration = "500ms"
+
+[[steps]]
+name = "Slow"
+protocol = "http"
+method = "GET"
+url = "{}/slow"
+"#,
+        mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    let runner = TestRunner::new(scenario);
+
+    let start = Instant::now();
+    let summary = runner.run().await.expect("Test execution failed");
+
+    assert!(summary.total_requests > 0);
+    assert!(summary.total_requests < 1000, "max_duration did not cut the run short");
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn test_named_scenarios_run_in_parallel_with_offsets() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/browse"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/checkout"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Shop"
+
+[[scenarios]]
+name = "browsing"
+
+[scenarios.load_profile]
+type = "constant"
+users = 2
+duration = "1s"
+
+[[scenarios.steps]]
+name = "Browse"
+protocol = "http"
+method = "GET"
+url = "{uri}/browse"
+
+[[scenarios]]
+name = "checkout"
+start_after = "500ms"
+
+[scenarios.load_profile]
+type = "per_vu_iterations"
+vus = 1
+iterations = 3
+
+[[scenarios.steps]]
+name = "Checkout"
+protocol = "http"
+method = "POST"
+url = "{uri}/checkout"
+"#,
+        uri = mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    scenario.validate().expect("Scenario validation failed");
+    let runner = TestRunner::new(scenario);
+
+    let start = Instant::now();
+    let summary = runner.run().await.expect("Test execution failed");
+
+    assert_eq!(summary.failed_requests, 0);
+    assert_eq!(summary.scenarios["checkout"].total_requests, 3);
+    assert!(summary.scenarios["browsing"].total_requests > 0);
+    assert_eq!(
+        summary.total_requests,
+        summary.scenarios["browsing"].total_requests + summary.scenarios["checkout"].total_requests
+    );
+    let steps: Vec<_> =
+        summary.steps.iter().map(|s| (s.scenario.as_str(), s.step.as_str())).collect();
+    assert_eq!(steps, [("browsing", "Browse"), ("checkout", "Checkout")]);
+    assert_eq!(summary.steps[1].total_requests, 3);
+    // Both scenarios overlap, so the run takes about as long as the longest one
+    assert!(start.elapsed() < Duration::from_millis(1800));
+}
+
+#[tokio::test]
+async fn test_required_extraction_failure_fails_step() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(r#"{"token": "abc123"}"#)
+                .insert_header("Set-Cookie", "session=xyz; Path=/"),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Extraction Test"
+
+[load_profile]
+type = "per_vu_iterations"
+vus = 1
+iterations = 2
+
+[[steps]]
+name = "Login"
+protocol = "http"
+method = "POST"
+url = "{uri}/login"
+
+[steps.extract]
+token = {{ from = "body", type = "jsonpath", expr = "$.token" }}
+session = {{ from = "cookie", type = "cookie", expr = "session" }}
+trace = {{ from = "header", type = "header", expr = "X-Trace-Id", required = false }}
+
+[[steps]]
+name = "Login Again"
+protocol = "http"
+method = "POST"
+url = "{uri}/login"
+
+[steps.extract]
+user_id = {{ from = "body", type = "jsonpath", expr = "$.user.id" }}
+"#,
+        uri = mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    let runner = TestRunner::new(scenario);
+    let summary = runner.run().await.expect("Test execution failed");
+
+    assert_eq!(summary.total_requests, 4);
+    assert_eq!(summary.failed_requests, 2, "Only the missing required extraction should fail");
+    assert!(
+        summary.errors_by_type.keys().any(|e| e.contains("Extraction of 'user_id' failed")),
+        "Unexpected errors: {:?}",
+        summary.errors_by_type
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_extractor_is_rejected_before_running() {
+    let toml = r#"
+[scenario]
+name = "Bad Extractor"
+
+[load_profile]
+type = "per_vu_iterations"
+vus = 1
+iterations = 1
+
+[[steps]]
+name = "Step"
+protocol = "http"
+method = "GET"
+url = "http://127.0.0.1:1/"
+
+[steps.extract]
+broken = { from = "body", type = "regex", expr = "(" }
+"#;
+
+    let scenario = Scenario::from_toml(toml).expect("Failed to parse scenario");
+    let runner = TestRunner::new(scenario);
+    assert!(runner.run().await.is_err());
+}
+
+#[tokio::test]
+async fn test_extracted_variables_are_interpolated() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_string(r#"{"token": "abc123", "item": 42}"#),
+        )
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("PUT"))
+        .and(path("/items/42"))
+        .and(header("Authorization", "Bearer abc123"))
+        .and(query_param("vu", "0"))
+        .and(body_string(r#"{"owner": "abc123"}"#))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Correlation Test"
+
+[load_profile]
+type = "per_vu_iterations"
+vus = 1
+iterations = 2
+
+[[steps]]
+name = "Login"
+protocol = "http"
+method = "POST"
+url = "{uri}/login"
+
+[steps.extract]
+token = {{ from = "body", type = "jsonpath", expr = "$.token" }}
+item_id = {{ from = "body", type = "jsonpath", expr = "$.item" }}
+
+[[steps]]
+name = "Update Item"
+protocol = "http"
+method = "PUT"
+url = "{uri}/items/{{{{item_id}}}}?vu={{{{__vu}}}}"
+body = '{{"owner": "{{{{token}}}}"}}'
+
+[steps.headers]
+Authorization = "Bearer {{{{token}}}}"
+"#,
+        uri = mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    scenario.validate().expect("Scenario validation failed");
+    let runner = TestRunner::new(scenario);
+    let summary = runner.run().await.expect("Test execution failed");
+
+    assert_eq!(summary.total_requests, 4);
+    assert_eq!(summary.failed_requests, 0, "Errors: {:?}", summary.errors_by_type);
+}
+
+#[tokio::test]
+async fn test_sequential_data_feeder_uses_each_row_once() {
+    let mock_server = MockServer::start().await;
+    for user in ["alice", "bob", "carol"] {
+        Mock::given(method("POST"))
+            .and(path("/login"))
+            .and(body_string(format!("user={user}")))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("users.csv"), "username\nalice\nbob\ncarol\n").unwrap();
+    let toml = format!(
+        r#"
+[scenario]
+name = "Data Feeder Test"
+
+[load_profile]
+type = "per_vu_iterations"
+vus = 2
+iterations = 5
+
+[[data]]
+file = "users.csv"
+
+[[steps]]
+name = "Login"
+protocol = "http"
+method = "POST"
+url = "{}/login"
+body = "user={{{{username}}}}"
+"#,
+        mock_server.uri()
+    );
+    let path = dir.path().join("scenario.toml");
+    std::fs::write(&path, toml).unwrap();
+
+    let scenario = Scenario::from_file(&path).expect("Failed to load scenario");
+    scenario.validate().expect("Scenario validation failed");
+    let runner = TestRunner::new(scenario);
+    let summary = runner.run().await.expect("Test execution failed");
+
+    // Three rows for ten iterations: both VUs stop once the file is used up
+    assert_eq!(summary.total_requests, 3);
+    assert_eq!(summary.failed_requests, 0, "Errors: {:?}", summary.errors_by_type);
+}
+
+#[tokio::test]
+async fn test_exhausted_data_feeder_stops_test() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET")).respond_with(ResponseTemplate::new(200)).mount(&mock_server).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("ids.jsonl"), "{\"id\": 1}\n{\"id\": 2}\n").unwrap();
+    let toml = format!(
+        r#"
+[scenario]
+name = "Stop Test"
+
+[load_profile]
+type = "constant"
+users = 2
+duration = "30s"
+
+[[data]]
+file = "ids.jsonl"
+on_exhausted = "stop_test"
+
+[[steps]]
+name = "Get Item"
+protocol = "http"
+method = "GET"
+url = "{}/items/{{{{id}}}}"
+"#,
+        mock_server.uri()
+    );
+    let path = dir.path().join("scenario.toml");
+    std::fs::write(&path, toml).unwrap();
+
+    let scenario = Scenario::from_file(&path).expect("Failed to load scenario");
+    let runner = TestRunner::new(scenario);
+    let started = Instant::now();
+    let summary = runner.run().await.expect("Test execution failed");
+
+    assert_eq!(summary.total_requests, 2);
+    assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+}
+
+#[tokio::test]
+async fn test_http_client_modes() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/fresh"))
+        .and(header("connection", "close"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/pooled"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Client Modes"
+
+[http]
+client = "shared"
+
+[[scenarios]]
+name = "shared"
+
+[scenarios.load_profile]
+type = "shared_iterations"
+vus = 4
+iterations = 20
+
+[[scenarios.steps]]
+name = "Pooled"
+protocol = "http"
+method = "GET"
+url = "{uri}/pooled"
+
+[[scenarios]]
+name = "no_keep_alive"
+http = {{ client = "no_keep_alive" }}
+
+[scenarios.load_profile]
+type = "per_vu_iterations"
+vus = 2
+iterations = 5
+
+[[scenarios.steps]]
+name = "Fresh"
+protocol = "http"
+method = "GET"
+url = "{uri}/fresh"
+"#,
+        uri = mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    scenario.validate().expect("Scenario validation failed");
+    let runner = TestRunner::new(scenario);
+    let summary = runner.run().await.expect("Test execution failed");
+
+    assert_eq!(summary.total_requests, 30);
+    assert_eq!(summary.failed_requests, 0, "Errors: {:?}", summary.errors_by_type);
+}
+
+/// Protocol that succeeds without touching the network
+#[derive(Debug)]
+struct NoopProtocol {
+    scenario: String,
+    step: String,
+}
+
+#[async_trait::async_trait]
+impl Protocol for NoopProtocol {
+    async fn execute(
+        &self,
+        _session: &mut Session,
+        _context: &mut VirtualUserContext,
+    ) -> taran_core::Result<StepResult> {
+        Ok(StepResult {
+            scenario: self.scenario.clone(),
+            step_name: self.step.clone(),
+            success: true,
+            duration: Duration::from_millis(1),
+            error: None,
+            status_code: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            started_at: None,
+            scheduled_at: None,
+            expected_interval: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_custom_protocol_from_registry() {
+    let toml = r#"
+[scenario]
+name = "Custom Protocol"
+
+[load_profile]
+type = "per_vu_iterations"
+vus = 2
+iterations = 3
+
+[[steps]]
+name = "Noop"
+protocol = "noop"
+method = "SEND"
+url = "noop://"
+"#;
+    let scenario = Scenario::from_toml(toml).expect("Failed to parse scenario");
+    scenario.validate().expect("Scenario validation failed");
+
+    // Unknown to the built-in registry: rejected before any load starts
+    assert!(ProtocolRegistry::default().check(&scenario).is_err());
+    assert!(TestRunner::new(scenario.clone()).run().await.is_err());
+
+    let mut registry = ProtocolRegistry::default();
+    registry.register("noop", |spec: &StepSpec<'_>| {
+        Ok(Box::new(NoopProtocol {
+            scenario: spec.scenario.to_string(),
+            step: spec.step.name.clone(),
+        }))
+    });
+    assert!(registry.check(&scenario).is_ok());
+
+    let summary =
+        TestRunner::with_registry(scenario, registry).run().await.expect("Test execution failed");
+    assert_eq!(summary.total_requests, 6);
+    assert_eq!(summary.failed_requests, 0);
+}
+
+#[tokio::test]
+async fn test_time_series_snapshots() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/series"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(10)))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Time series"
+
+[metrics]
+snapshot_interval = "250ms"
+
+[load_profile]
+type = "constant"
+users = 2
+duration = "1s"
+
+[[steps]]
+name = "Series"
+protocol = "http"
+method = "GET"
+url = "{}/series"
+"#,
+        mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    scenario.validate().expect("Scenario validation failed");
+    let summary = TestRunner::new(scenario).run().await.expect("Test execution failed");
+
+    let timeline = &summary.timeline;
+    assert!((4..=6).contains(&timeline.len()), "{} snapshots", timeline.len());
+    assert_eq!(timeline.iter().map(|s| s.total_requests).sum::<u64>(), summary.total_requests);
+    assert!(timeline.windows(2).all(|w| w[0].elapsed_secs < w[1].elapsed_secs));
+    let first = &timeline[0];
+    assert_eq!(first.active_vus, 2);
+    assert!(first.requests_per_sec > 0.0);
+    assert!(first.service_time.p50_ms >= 10.0);
+    assert_eq!(first.steps[0].step, "Series");
+}
+
+#[tokio::test]
+async fn test_expected_interval_back_fills_closed_loop() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(200)))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Paced"
+
+[metrics]
+expected_interval = "50ms"
+
+[load_profile]
+type = "per_vu_iterations"
+vus = 1
+iterations = 2
+
+[[steps]]
+name = "Slow"
+protocol = "http"
+method = "GET"
+url = "{}/slow"
+"#,
+        mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    scenario.validate().expect("Scenario validation failed");
+    let summary = TestRunner::new(scenario).run().await.expect("Test execution failed");
+
+    assert_eq!(summary.total_requests, 2);
+    // Each 200ms response stands in for requests due every 50ms meanwhile
+    assert!(summary.service_time.min_ms >= 200.0);
+    assert!(summary.response_time.min_ms < 100.0, "{}", summary.response_time.min_ms);
+    assert!(summary.response_time.max_ms >= 200.0);
+}
+
+#[tokio::test]
+async fn test_thresholds_are_evaluated() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/gate"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(50)))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Gated"
+
+[thresholds]
+rules = ["http_req_duration.p95 < 5s", "error_rate < 1%", '"Gate".p99 < 10ms', "requests == 4"]
+
+[load_profile]
+type = "per_vu_iterations"
+vus = 2
+iterations = 2
+
+[[steps]]
+name = "Gate"
+protocol = "http"
+method = "GET"
+url = "{}/gate"
+"#,
+        mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    scenario.validate().expect("Scenario validation failed");
+    let summary = TestRunner::new(scenario).run().await.expect("Test execution failed");
+
+    let passed: Vec<_> = summary.thresholds.iter().map(|t| t.passed).collect();
+    assert_eq!(passed, [true, true, false, true]);
+    assert!(summary.thresholds[2].actual.unwrap() >= 50.0);
+    assert!(!summary.thresholds_passed());
+}
+
+#[tokio::test]
+async fn test_abort_on_fail_stops_the_run() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/down"))
+        .respond_with(ResponseTemplate::new(503).set_delay(Duration::from_millis(10)))
+        .mount(&mock_server)
+        .await;
+
+    let toml = format!(
+        r#"
+[scenario]
+name = "Runaway"
+
+[metrics]
+snapshot_interval = "200ms"
+
+[thresholds]
+rules = [
+  "p95 < 5s",
+  {{ threshold = "error_rate < 10%", abort_on_fail = true, delay_abort_eval = "500ms" }},
+]
+
+[load_profile]
+type = "constant"
+users = 2
+duration = "30s"
+
+[[steps]]
+name = "Down"
+protocol = "http"
+method = "GET"
+url = "{}/down"
+"#,
+        mock_server.uri()
+    );
+
+    let scenario = Scenario::from_toml(&toml).expect("Failed to parse scenario");
+    scenario.validate().expect("Scenario validation failed");
+
+    let start = Instant::now();
+    let summary = TestRunner::new(scenario).run().await.expect("Test execution failed");
+    let elapsed = start.elapsed();
+
+    // Not before the grace period, and long before the 30s profile ends
+    assert!(elapsed >= Duration::from_millis(500), "{elapsed:?}");
+    assert!(elapsed < Duration::from_secs(3), "{elapsed:?}");
+    let aborted = summary.aborted_by().expect("run was not aborted");
+    assert_eq!(aborted.expression, "error_rate < 10%");
+    assert!(!aborted.passed);
+    assert!(summary.thresholds[0].passed);
+    assert!(!summary.thresholds_passed());
+    assert!(summary.timeline.len() >= 3);
+}
+
+/// Iterations started by [`SlowProtocol`]
+static SLOW_STARTED: AtomicUsize = AtomicUsize::new(0);
+
+/// Protocol that keeps its VU busy for 300ms
+#[derive(Debug)]
+struct SlowProtocol;
+
+#[async_trait::async_trait]
+impl Protocol for SlowProtocol {
+    async fn execute(
+        &self,
+        _session: &mut Session,
+        _context: &mut VirtualUserContext,
+    ) -> taran_core::Result<StepResult> {
+        SLOW_STARTED.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        Ok(StepResult {
+            scenario: "Open Loop".to_string(),
+            step_name: "Slow".to_string(),
+            success: true,
+            duration: Duration::from_millis(300),
+            error: None,
+            status_code: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            started_at: None,
+            scheduled_at: None,
+            expected_interval: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_arrival_rate_stops_while_every_vu_is_busy() {
+    let toml = r#"
+[scenario]
+name = "Open Loop"
+
+[load_profile]
+type = "constant_arrival_rate"
+rate = 100
+duration = "10s"
+max_vus = 1
+
+[[steps]]
+name = "Slow"
+protocol = "slow"
+method = "SEND"
+url = "slow://"
+"#;
+    let scenario = Scenario::from_toml(toml).expect("Failed to parse scenario");
+    let mut registry = ProtocolRegistry::default();
+    registry.register("slow", |_: &StepSpec<'_>| Ok(Box::new(SlowProtocol)));
+    let named = &scenario.named_scenarios()[0];
+    let plan = Arc::new(ScenarioPlan::new(named, &[], &registry).unwrap());
+    let profile = ArrivalRateProfile::from_config(&named.load_profile).unwrap();
+    let executor = ArrivalRateExecutor::new(profile, 1);
+
+    let run = RunContext::new(HdrCollector::new().unwrap());
+    let running = tokio::spawn(executor.run(plan, run.clone()));
+    while SLOW_STARTED.load(Ordering::SeqCst) == 0 {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    // The next iteration is due within 10ms and waits for the only VU
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    run.stop.stop();
+
+    let start = Instant::now();
+    running.await.unwrap();
+    assert!(start.elapsed() < Duration::from_secs(1), "{:?}", start.elapsed());
+    assert_eq!(SLOW_STARTED.load(Ordering::SeqCst), 1);
+    assert_eq!(run.collector.counters().total_requests, 1);
+}
@@ -1,33 +1,156 @@
+use crate::error::{MetricsError, Result};
+use crate::histogram::{HistogramData, StepHistogram};
+use crate::threshold::ThresholdOutcome;
+use hdrhistogram::Histogram;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use thread_local::ThreadLocal;
 
-/// Simple metrics collector using Mutex<HashMap>
-/// This is Phase 0 implementation - will be replaced with lock-free HDR in Phase 3
+/// Highest latency the histograms can hold (1 hour); slower requests are
+/// clamped to it
+const MAX_LATENCY_US: u64 = 3_600_000_000;
+
+/// Significant decimal digits kept by every histogram (0.1% precision)
+const SIGNIFICANT_DIGITS: u8 = 3;
+
+/// Most points kept in the time series.
+///
+/// Once it is full, neighbouring points are merged pairwise and every later
+/// point covers twice as many windows, so a run of any length keeps its
+/// whole shape in bounded memory. With the default 1s interval the first
+/// 16 minutes keep full resolution. Every point keeps its recorded latency
+/// buckets, so the percentiles of a merged point are exact.
+pub const MAX_TIMELINE_POINTS: usize = 1000;
+
+/// Metrics collector backed by HDR histograms at microsecond resolution.
+///
+/// Request and byte counters are atomics. Latencies are recorded into a
+/// shard owned by the recording worker thread, so VUs never contend on a
+/// shared lock; [`merge`](Self::merge) folds the shards into one aggregate
+/// and is called at every [`record_interval`](Self::record_interval) and
+/// before every summary. Histograms have a fixed size, so memory stays flat
+/// however long the run.
 #[derive(Debug, Clone)]
-pub struct SimpleCollector {
-    inner: Arc<Mutex<CollectorInner>>,
+pub struct HdrCollector {
+    inner: Arc<CollectorInner>,
 }
 
 #[derive(Debug)]
 struct CollectorInner {
-    total_requests: u64,
-    successful_requests: u64,
-    failed_requests: u64,
-    total_bytes_sent: u64,
-    total_bytes_received: u64,
-    latencies: Vec<Duration>,
+    total_requests: AtomicU64,
+    successful_requests: AtomicU64,
+    failed_requests: AtomicU64,
+    total_bytes_sent: AtomicU64,
+    total_bytes_received: AtomicU64,
+    total_latency_us: AtomicU64,
+    active_vus: AtomicU64,
+    /// Empty histogram cloned for every new step, so creating one never fails
+    template: Histogram<u64>,
+    /// Empty auto-resizing histogram for rebuilding closed windows, sized
+    /// by the latencies they hold rather than the whole range
+    sparse_template: Histogram<u64>,
+    shards: ThreadLocal<Mutex<Shard>>,
+    aggregate: Mutex<Aggregate>,
+}
+
+/// Everything merged from the worker shards
+#[derive(Debug)]
+struct Aggregate {
+    /// Since the collector was created or reset
+    total: Shard,
+    /// Since the last recorded interval
+    window: Shard,
+    started: Instant,
+    window_started: Instant,
+    timeline: Vec<TimelinePoint>,
+    /// Windows merged into every point of the timeline
+    windows_per_point: usize,
+    /// Windows merged into the last point so far
+    last_point_windows: usize,
+    /// The last windows, oldest first, see [`HdrCollector::recent`]
+    recent: VecDeque<ClosedWindow>,
+    recent_limit: usize,
+}
+
+/// A point of the timeline: one or more windows, turned into an
+/// [`IntervalSnapshot`] by the summary
+#[derive(Debug, Clone)]
+struct TimelinePoint {
+    window: ClosedWindow,
+    /// Sampled when the last window closed
+    active_vus: u64,
+}
+
+/// One or more closed windows, kept for [`HdrCollector::recent`] and the
+/// timeline. Only recorded histogram buckets are kept, so a window costs
+/// memory in proportion to the distinct latencies seen rather than a full
+/// histogram per step.
+#[derive(Debug, Clone)]
+struct ClosedWindow {
+    elapsed_secs: f64,
+    duration_secs: f64,
+    steps: Vec<(String, String, ClosedStats)>,
+}
+
+#[derive(Debug, Clone)]
+struct ClosedStats {
+    service: HistogramData,
+    response: HistogramData,
+    successes: u64,
+    failures: u64,
+}
+
+/// Latencies and errors recorded by one worker thread since the last merge
+#[derive(Debug, Default)]
+struct Shard {
+    /// Scenario name → step name → stats
+    steps: HashMap<String, HashMap<String, StepStats>>,
     errors: HashMap<String, u64>,
-    step_metrics: HashMap<String, StepMetrics>,
 }
 
-#[derive(Debug, Clone, Default)]
-struct StepMetrics {
-    requests: u64,
+#[derive(Debug, Clone)]
+struct StepStats {
+    service: Histogram<u64>,
+    response: Histogram<u64>,
     successes: u64,
     failures: u64,
-    total_latency_ms: u64,
+    total_latency_us: u64,
+}
+
+/// How long one request took, measured two ways.
+///
+/// Without a schedule both are the same; [`From<Duration>`] builds that case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    /// Actual start to completion: how long the system under test took
+    pub service: Duration,
+    /// Scheduled start to completion: how long a user would have waited,
+    /// including any delay caused by the load generator falling behind
+    pub response: Duration,
+    /// Back-fill the response-time histogram as if a request had been due
+    /// every interval, like `Histogram::record_correct`. Only meaningful
+    /// for closed-loop requests whose schedule is not otherwise known.
+    pub expected_interval: Option<Duration>,
+}
+
+impl From<Duration> for Timing {
+    fn from(latency: Duration) -> Self {
+        Self { service: latency, response: latency, expected_interval: None }
+    }
+}
+
+/// Point-in-time copy of the collector's atomic counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counters {
+    pub total_requests: u64,
+    pub successful_requests: u64,
+    pub failed_requests: u64,
+    pub total_bytes_sent: u64,
+    pub total_bytes_received: u64,
+    pub total_latency_us: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +161,8 @@ pub struct MetricsSummary {
     pub success_rate: f64,
     pub total_bytes_sent: u64,
     pub total_bytes_received: u64,
+    /// Service time in whole milliseconds; see `service_time` and
+    /// `response_time` for both distributions at full precision
     pub avg_latency_ms: f64,
     pub min_latency_ms: u64,
     pub max_latency_ms: u64,
@@ -45,148 +170,775 @@ pub struct MetricsSummary {
     pub p95_latency_ms: u64,
     pub p99_latency_ms: u64,
     pub errors_by_type: HashMap<String, u64>,
+    /// Actual start to completion of every request
+    #[serde(default)]
+    pub service_time: LatencyDistribution,
+    /// Scheduled start to completion, corrected for coordinated omission
+    #[serde(default)]
+    pub response_time: LatencyDistribution,
+    /// Seconds since the collector was created or reset
+    #[serde(default)]
+    pub duration_secs: f64,
+    #[serde(default)]
+    pub requests_per_sec: f64,
+    /// Breakdown by named scenario
+    #[serde(default)]
+    pub scenarios: HashMap<String, ScenarioSummary>,
+    /// Breakdown by step, sorted by scenario and step name
+    #[serde(default)]
+    pub steps: Vec<StepSummary>,
+    /// Metrics of every recorded interval, oldest first
+    #[serde(default)]
+    pub timeline: Vec<IntervalSnapshot>,
+    /// Outcome of every threshold rule, filled in after the run
+    #[serde(default)]
+    pub thresholds: Vec<ThresholdOutcome>,
+    /// Raw latency histograms of every step, in the same order as `steps`
+    #[serde(default)]
+    pub histograms: Vec<StepHistogram>,
+}
+
+impl MetricsSummary {
+    /// Whether every threshold passed; `true` when there are none
+    pub fn thresholds_passed(&self) -> bool {
+        self.thresholds.iter().all(|outcome| outcome.passed)
+    }
+
+    /// Whether the run passed: every threshold held or, without any
+    /// thresholds, no request failed
+    pub fn passed(&self) -> bool {
+        if self.thresholds.is_empty() {
+            self.failed_requests == 0
+        } else {
+            self.thresholds_passed()
+        }
+    }
+
+    /// The threshold that stopped the run early, if any
+    pub fn aborted_by(&self) -> Option<&ThresholdOutcome> {
+        self.thresholds.iter().find(|outcome| outcome.aborted)
+    }
 }
 
-impl SimpleCollector {
-    /// Lock inner state, recovering from mutex poisoning.
-    /// Poisoning means a thread panicked while holding the lock —
-    /// we still want to access the data rather than propagate panic.
-    fn lock_inner(&self) -> std::sync::MutexGuard<'_, CollectorInner> {
-        self.inner.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+/// Metrics of one time-series window.
+///
+/// Counts and latencies cover only requests completed within the window;
+/// `active_vus` is sampled when it closes. Past [`MAX_TIMELINE_POINTS`] a
+/// point of the run's timeline covers several windows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntervalSnapshot {
+    /// Seconds from the start of the run to the end of the window
+    pub elapsed_secs: f64,
+    pub duration_secs: f64,
+    pub active_vus: u64,
+    pub total_requests: u64,
+    pub failed_requests: u64,
+    pub requests_per_sec: f64,
+    /// Percentage of failed requests
+    pub error_rate: f64,
+    pub service_time: LatencyDistribution,
+    pub response_time: LatencyDistribution,
+    /// Steps with requests in the window, sorted by scenario and step name
+    pub steps: Vec<StepSummary>,
+}
+
+/// Keeps a VU counted as active until dropped, see [`HdrCollector::track_vu`]
+#[derive(Debug)]
+pub struct ActiveVu {
+    inner: Arc<CollectorInner>,
+}
+
+impl Drop for ActiveVu {
+    fn drop(&mut self) {
+        self.inner.active_vus.fetch_sub(1, Ordering::Relaxed);
     }
+}
+
+/// Request counts and average latency for one named scenario
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioSummary {
+    pub total_requests: u64,
+    pub successful_requests: u64,
+    pub failed_requests: u64,
+    pub avg_latency_ms: f64,
+}
 
-    pub fn new() -> Self {
+/// Request counts, throughput and latency distributions of one step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepSummary {
+    pub scenario: String,
+    pub step: String,
+    pub total_requests: u64,
+    pub successful_requests: u64,
+    pub failed_requests: u64,
+    pub requests_per_sec: f64,
+    /// Actual start to completion
+    pub service_time: LatencyDistribution,
+    /// Scheduled start to completion, corrected for coordinated omission
+    pub response_time: LatencyDistribution,
+}
+
+/// Latency percentiles in milliseconds with microsecond precision
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyDistribution {
+    pub avg_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub p50_ms: f64,
+    pub p75_ms: f64,
+    pub p90_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub p999_ms: f64,
+}
+
+impl StepSummary {
+    fn new(scenario: &str, step: &str, stats: &StepStats, duration_secs: f64) -> Self {
+        let total_requests = stats.successes + stats.failures;
         Self {
-            inner: Arc::new(Mutex::new(CollectorInner {
-                total_requests: 0,
-                successful_requests: 0,
-                failed_requests: 0,
-                total_bytes_sent: 0,
-                total_bytes_received: 0,
-                latencies: Vec::new(),
-                errors: HashMap::new(),
-                step_metrics: HashMap::new(),
-            })),
+            scenario: scenario.to_string(),
+            step: step.to_string(),
+            total_requests,
+            successful_requests: stats.successes,
+            failed_requests: stats.failures,
+            requests_per_sec: rate(total_requests, duration_secs),
+            service_time: LatencyDistribution::from_histogram(&stats.service),
+            response_time: LatencyDistribution::from_histogram(&stats.response),
         }
     }
+}
+
+impl LatencyDistribution {
+    pub(crate) fn from_histogram(histogram: &Histogram<u64>) -> Self {
+        if histogram.is_empty() {
+            return Self::default();
+        }
+        let quantile = |q| us_to_ms_f64(histogram.value_at_quantile(q));
+        Self {
+            avg_ms: histogram.mean() / 1000.0,
+            min_ms: us_to_ms_f64(histogram.min()),
+            max_ms: us_to_ms_f64(histogram.max()),
+            p50_ms: quantile(0.50),
+            p75_ms: quantile(0.75),
+            p90_ms: quantile(0.90),
+            p95_ms: quantile(0.95),
+            p99_ms: quantile(0.99),
+            p999_ms: quantile(0.999),
+        }
+    }
+}
+
+impl HdrCollector {
+    pub fn new() -> Result<Self> {
+        let template = Histogram::new_with_bounds(1, MAX_LATENCY_US, SIGNIFICANT_DIGITS)
+            .map_err(|e| MetricsError::HistogramError(e.to_string()))?;
+        let sparse_template = Histogram::new(SIGNIFICANT_DIGITS)
+            .map_err(|e| MetricsError::HistogramError(e.to_string()))?;
+        Ok(Self {
+            inner: Arc::new(CollectorInner {
+                total_requests: AtomicU64::new(0),
+                successful_requests: AtomicU64::new(0),
+                failed_requests: AtomicU64::new(0),
+                total_bytes_sent: AtomicU64::new(0),
+                total_bytes_received: AtomicU64::new(0),
+                total_latency_us: AtomicU64::new(0),
+                active_vus: AtomicU64::new(0),
+                template,
+                sparse_template,
+                shards: ThreadLocal::new(),
+                aggregate: Mutex::new(Aggregate::new()),
+            }),
+        })
+    }
 
     /// Record a successful request
     pub fn record_success(
         &self,
+        scenario: &str,
         step_name: &str,
-        latency: Duration,
+        timing: impl Into<Timing>,
         bytes_sent: u64,
         bytes_received: u64,
     ) {
-        let mut inner = self.lock_inner();
-        inner.total_requests += 1;
-        inner.successful_requests += 1;
-        inner.total_bytes_sent += bytes_sent;
-        inner.total_bytes_received += bytes_received;
-        inner.latencies.push(latency);
+        let inner = &self.inner;
+        let timing = timing.into();
+        inner.successful_requests.fetch_add(1, Ordering::Relaxed);
+        inner.total_bytes_sent.fetch_add(bytes_sent, Ordering::Relaxed);
+        inner.total_bytes_received.fetch_add(bytes_received, Ordering::Relaxed);
+        self.count_request(timing.service);
 
-        let metrics = inner.step_metrics.entry(step_name.to_string()).or_default();
-        metrics.requests += 1;
-        metrics.successes += 1;
-        metrics.total_latency_ms += latency.as_millis() as u64;
+        let mut shard = self.local_shard();
+        if let Some(stats) = shard.step_mut(scenario, step_name, &inner.template) {
+            stats.record(&timing);
+            stats.successes += 1;
+        }
     }
 
     /// Record a failed request
-    pub fn record_failure(&self, step_name: &str, error: &str, latency: Duration) {
-        let mut inner = self.lock_inner();
-        inner.total_requests += 1;
-        inner.failed_requests += 1;
-        inner.latencies.push(latency);
+    pub fn record_failure(
+        &self,
+        scenario: &str,
+        step_name: &str,
+        error: &str,
+        timing: impl Into<Timing>,
+    ) {
+        let timing = timing.into();
+        self.inner.failed_requests.fetch_add(1, Ordering::Relaxed);
+        self.count_request(timing.service);
+
+        let mut shard = self.local_shard();
+        if let Some(stats) = shard.step_mut(scenario, step_name, &self.inner.template) {
+            stats.record(&timing);
+            stats.failures += 1;
+        }
+        match shard.errors.get_mut(error) {
+            Some(count) => *count += 1,
+            None => {
+                shard.errors.insert(error.to_string(), 1);
+            }
+        }
+    }
 
-        *inner.errors.entry(error.to_string()).or_insert(0) += 1;
+    /// Count a VU as active for as long as the returned guard lives
+    pub fn track_vu(&self) -> ActiveVu {
+        self.inner.active_vus.fetch_add(1, Ordering::Relaxed);
+        ActiveVu { inner: Arc::clone(&self.inner) }
+    }
+
+    pub fn active_vus(&self) -> u64 {
+        self.inner.active_vus.load(Ordering::Relaxed)
+    }
+
+    /// Current values of the request, byte and latency counters
+    pub fn counters(&self) -> Counters {
+        let inner = &self.inner;
+        Counters {
+            total_requests: inner.total_requests.load(Ordering::Relaxed),
+            successful_requests: inner.successful_requests.load(Ordering::Relaxed),
+            failed_requests: inner.failed_requests.load(Ordering::Relaxed),
+            total_bytes_sent: inner.total_bytes_sent.load(Ordering::Relaxed),
+            total_bytes_received: inner.total_bytes_received.load(Ordering::Relaxed),
+            total_latency_us: inner.total_latency_us.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Fold every worker shard into the aggregate.
+    ///
+    /// Shard histograms are reset in place rather than dropped, so workers
+    /// keep their allocations and the hot path never reallocates.
+    pub fn merge(&self) {
+        self.merge_into(&mut lock(&self.inner.aggregate));
+    }
+
+    /// Close the current time-series window and start the next one.
+    ///
+    /// The runner calls this every snapshot interval and once more when the
+    /// run ends, so the last, possibly shorter, window is kept as well.
+    pub fn record_interval(&self) -> IntervalSnapshot {
+        let mut aggregate = lock(&self.inner.aggregate);
+        self.merge_into(&mut aggregate);
+
+        let now = Instant::now();
+        let elapsed_secs = now.duration_since(aggregate.started).as_secs_f64();
+        let duration_secs = now.duration_since(aggregate.window_started).as_secs_f64();
+        let snapshot = self.snapshot(&aggregate.window, elapsed_secs, duration_secs);
+        let window = ClosedWindow::new(&aggregate.window, elapsed_secs, duration_secs);
+        if aggregate.recent_limit > 0 {
+            aggregate.recent.push_back(window.clone());
+            while aggregate.recent.len() > aggregate.recent_limit {
+                aggregate.recent.pop_front();
+            }
+        }
+        aggregate.window.clear();
+        aggregate.window_started = now;
+        aggregate.push_point(TimelinePoint { window, active_vus: snapshot.active_vus });
+        snapshot
+    }
+
+    /// Keep the last `windows` recorded windows for [`recent`](Self::recent);
+    /// none are kept by default
+    pub fn keep_recent_windows(&self, windows: usize) {
+        let mut aggregate = lock(&self.inner.aggregate);
+        aggregate.recent_limit = windows;
+        while aggregate.recent.len() > windows {
+            aggregate.recent.pop_front();
+        }
+    }
+
+    /// The windows kept by [`keep_recent_windows`](Self::keep_recent_windows)
+    /// as one rolling window ending with the last recorded one.
+    ///
+    /// Latencies are exact: the windows' histograms are added up, not their
+    /// percentiles.
+    pub fn recent(&self) -> IntervalSnapshot {
+        let aggregate = lock(&self.inner.aggregate);
+        let template = &self.inner.sparse_template;
+        let mut merged = Shard::default();
+        for window in &aggregate.recent {
+            window.add_to(&mut merged, template);
+        }
+        let elapsed_secs = aggregate.recent.back().map_or(0.0, |window| window.elapsed_secs);
+        let duration_secs = aggregate.recent.iter().map(|window| window.duration_secs).sum();
+        snapshot(&merged, template, elapsed_secs, duration_secs, self.active_vus())
+    }
 
-        let metrics = inner.step_metrics.entry(step_name.to_string()).or_default();
-        metrics.requests += 1;
-        metrics.failures += 1;
-        metrics.total_latency_ms += latency.as_millis() as u64;
+    /// Metrics of everything recorded in `shard`
+    fn snapshot(&self, shard: &Shard, elapsed_secs: f64, duration_secs: f64) -> IntervalSnapshot {
+        snapshot(shard, &self.inner.template, elapsed_secs, duration_secs, self.active_vus())
     }
 
     /// Get a summary of all collected metrics
     pub fn summary(&self) -> MetricsSummary {
-        let inner = self.lock_inner();
+        self.merge();
+        let counters = self.counters();
+        let aggregate = lock(&self.inner.aggregate);
+        let duration_secs = aggregate.started.elapsed().as_secs_f64();
+        let merged = &aggregate.total;
 
-        let mut sorted_latencies: Vec<u64> =
-            inner.latencies.iter().map(|d| d.as_millis() as u64).collect();
-        sorted_latencies.sort_unstable();
+        let mut all = self.inner.template.clone();
+        let mut response = self.inner.template.clone();
+        let mut scenarios = HashMap::new();
+        let mut step_summaries = Vec::new();
+        let mut histograms = Vec::new();
+        for (scenario, steps) in &merged.steps {
+            let mut totals = ScenarioSummary {
+                total_requests: 0,
+                successful_requests: 0,
+                failed_requests: 0,
+                avg_latency_ms: 0.0,
+            };
+            let mut latency_us = 0;
+            for (step, stats) in steps {
+                step_summaries.push(StepSummary::new(scenario, step, stats, duration_secs));
+                histograms.push(StepHistogram {
+                    scenario: scenario.clone(),
+                    step: step.clone(),
+                    service: HistogramData::from_histogram(&stats.service),
+                    response: HistogramData::from_histogram(&stats.response),
+                });
+                add_histogram(&mut all, &stats.service);
+                add_histogram(&mut response, &stats.response);
+                totals.successful_requests += stats.successes;
+                totals.failed_requests += stats.failures;
+                latency_us += stats.total_latency_us;
+            }
+            totals.total_requests = totals.successful_requests + totals.failed_requests;
+            totals.avg_latency_ms = mean_ms(latency_us, totals.total_requests);
+            scenarios.insert(scenario.clone(), totals);
+        }
 
-        let (avg, min, max, p50, p95, p99) = if sorted_latencies.is_empty() {
-            (0.0, 0, 0, 0, 0, 0)
-        } else {
-            let sum: u64 = sorted_latencies.iter().sum();
-            let avg = sum as f64 / sorted_latencies.len() as f64;
-            let min = sorted_latencies[0];
-            let max = sorted_latencies[sorted_latencies.len() - 1];
-            let p50 = percentile(&sorted_latencies, 50.0);
-            let p95 = percentile(&sorted_latencies, 95.0);
-            let p99 = percentile(&sorted_latencies, 99.0);
-            (avg, min, max, p50, p95, p99)
-        };
+        step_summaries.sort_by(|a, b| (&a.scenario, &a.step).cmp(&(&b.scenario, &b.step)));
+        histograms.sort_by(|a, b| (&a.scenario, &a.step).cmp(&(&b.scenario, &b.step)));
 
-        let success_rate = if inner.total_requests > 0 {
-            (inner.successful_requests as f64 / inner.total_requests as f64) * 100.0
-        } else {
-            0.0
-        };
+        let success_rate = percentage(counters.successful_requests, counters.total_requests);
+        let (min, max) = if all.is_empty() { (0, 0) } else { (all.min(), all.max()) };
 
         MetricsSummary {
-            total_requests: inner.total_requests,
-            successful_requests: inner.successful_requests,
-            failed_requests: inner.failed_requests,
+            total_requests: counters.total_requests,
+            successful_requests: counters.successful_requests,
+            failed_requests: counters.failed_requests,
             success_rate,
-            total_bytes_sent: inner.total_bytes_sent,
-            total_bytes_received: inner.total_bytes_received,
-            avg_latency_ms: avg,
-            min_latency_ms: min,
-            max_latency_ms: max,
-            p50_latency_ms: p50,
-            p95_latency_ms: p95,
-            p99_latency_ms: p99,
-            errors_by_type: inner.errors.clone(),
+            total_bytes_sent: counters.total_bytes_sent,
+            total_bytes_received: counters.total_bytes_received,
+            avg_latency_ms: mean_ms(counters.total_latency_us, counters.total_requests),
+            min_latency_ms: us_to_ms(min),
+            max_latency_ms: us_to_ms(max),
+            p50_latency_ms: us_to_ms(all.value_at_quantile(0.50)),
+            p95_latency_ms: us_to_ms(all.value_at_quantile(0.95)),
+            p99_latency_ms: us_to_ms(all.value_at_quantile(0.99)),
+            errors_by_type: merged.errors.clone(),
+            service_time: LatencyDistribution::from_histogram(&all),
+            response_time: LatencyDistribution::from_histogram(&response),
+            duration_secs,
+            requests_per_sec: rate(counters.total_requests, duration_secs),
+            scenarios,
+            steps: step_summaries,
+            timeline: aggregate
+                .timeline
+                .iter()
+                .map(|point| point.snapshot(&self.inner.sparse_template))
+                .collect(),
+            thresholds: Vec::new(),
+            histograms,
         }
     }
 
     /// Reset all metrics
     pub fn reset(&self) {
-        let mut inner = self.lock_inner();
-        inner.total_requests = 0;
-        inner.successful_requests = 0;
-        inner.failed_requests = 0;
-        inner.total_bytes_sent = 0;
-        inner.total_bytes_received = 0;
-        inner.latencies.clear();
-        inner.errors.clear();
-        inner.step_metrics.clear();
+        let inner = &self.inner;
+        for counter in [
+            &inner.total_requests,
+            &inner.successful_requests,
+            &inner.failed_requests,
+            &inner.total_bytes_sent,
+            &inner.total_bytes_received,
+            &inner.total_latency_us,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+        for shard in &inner.shards {
+            *lock(shard) = Shard::default();
+        }
+        let mut aggregate = lock(&inner.aggregate);
+        *aggregate = Aggregate { recent_limit: aggregate.recent_limit, ..Aggregate::new() };
+    }
+
+    fn merge_into(&self, aggregate: &mut Aggregate) {
+        for shard in &self.inner.shards {
+            let mut shard = lock(shard);
+            aggregate.total.add(&shard, &self.inner.template);
+            aggregate.window.add(&shard, &self.inner.template);
+            shard.clear();
+        }
+    }
+
+    /// Count one request and its service time
+    fn count_request(&self, latency: Duration) {
+        self.inner.total_requests.fetch_add(1, Ordering::Relaxed);
+        self.inner.total_latency_us.fetch_add(micros(latency), Ordering::Relaxed);
+    }
+
+    /// The calling thread's shard. Only [`merge`](Self::merge) ever locks
+    /// it from another thread, so the lock is uncontended on the hot path.
+    fn local_shard(&self) -> MutexGuard<'_, Shard> {
+        lock(self.inner.shards.get_or(|| Mutex::new(Shard::default())))
     }
 }
 
-impl Default for SimpleCollector {
-    fn default() -> Self {
-        Self::new()
+impl Shard {
+    /// Stats for a step, creating them on first use. Keys are only
+    /// allocated then, so recording a known step never allocates.
+    fn step_mut(
+        &mut self,
+        scenario: &str,
+        step: &str,
+        template: &Histogram<u64>,
+    ) -> Option<&mut StepStats> {
+        if !self.steps.contains_key(scenario) {
+            self.steps.insert(scenario.to_string(), HashMap::new());
+        }
+        let steps = self.steps.get_mut(scenario)?;
+        if !steps.contains_key(step) {
+            steps.insert(step.to_string(), StepStats::new(template));
+        }
+        steps.get_mut(step)
+    }
+
+    /// Steps with at least one request, sorted by scenario and step name
+    fn recorded(&self) -> Vec<(&str, &str, &StepStats)> {
+        let mut recorded: Vec<_> = self
+            .steps
+            .iter()
+            .flat_map(|(scenario, steps)| {
+                steps.iter().map(move |(step, stats)| (scenario.as_str(), step.as_str(), stats))
+            })
+            .filter(|(_, _, stats)| stats.successes + stats.failures > 0)
+            .collect();
+        recorded.sort_by_key(|&(scenario, step, _)| (scenario, step));
+        recorded
+    }
+
+    /// Add everything recorded in `other`
+    fn add(&mut self, other: &Self, template: &Histogram<u64>) {
+        for (scenario, step, stats) in other.recorded() {
+            if let Some(merged) = self.step_mut(scenario, step, template) {
+                merged.add(stats);
+            }
+        }
+        for (error, count) in &other.errors {
+            match self.errors.get_mut(error) {
+                Some(total) => *total += count,
+                None => {
+                    self.errors.insert(error.clone(), *count);
+                }
+            }
+        }
+    }
+
+    /// Reset every step in place, keeping the histogram allocations
+    fn clear(&mut self) {
+        for stats in self.steps.values_mut().flat_map(HashMap::values_mut) {
+            stats.clear();
+        }
+        self.errors.clear();
     }
 }
 
-fn percentile(sorted_data: &[u64], p: f64) -> u64 {
-    if sorted_data.is_empty() {
-        return 0;
+impl Aggregate {
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            total: Shard::default(),
+            window: Shard::default(),
+            started: now,
+            window_started: now,
+            timeline: Vec::new(),
+            windows_per_point: 1,
+            last_point_windows: 0,
+            recent: VecDeque::new(),
+            recent_limit: 0,
+        }
+    }
+
+    /// Add a window to the timeline, halving its resolution once it holds
+    /// [`MAX_TIMELINE_POINTS`]
+    fn push_point(&mut self, point: TimelinePoint) {
+        match self.timeline.last_mut() {
+            Some(last) if self.last_point_windows < self.windows_per_point => {
+                *last = last.merged(&point);
+                self.last_point_windows += 1;
+            }
+            _ => {
+                self.timeline.push(point);
+                self.last_point_windows = 1;
+            }
+        }
+        if self.timeline.len() <= MAX_TIMELINE_POINTS {
+            return;
+        }
+
+        if self.timeline.len() % 2 == 0 {
+            self.last_point_windows += self.windows_per_point;
+        }
+        let mut points = std::mem::take(&mut self.timeline).into_iter();
+        while let Some(first) = points.next() {
+            let point = match points.next() {
+                Some(second) => first.merged(&second),
+                None => first,
+            };
+            self.timeline.push(point);
+        }
+        self.windows_per_point *= 2;
+    }
+}
+
+impl TimelinePoint {
+    /// One point covering this one and the `later` one that follows it.
+    ///
+    /// The windows' latency buckets are added up rather than their
+    /// percentiles, so the merged percentiles are exact.
+    fn merged(&self, later: &Self) -> Self {
+        Self { window: self.window.merged(&later.window), active_vus: later.active_vus }
+    }
+
+    fn snapshot(&self, template: &Histogram<u64>) -> IntervalSnapshot {
+        let mut shard = Shard::default();
+        self.window.add_to(&mut shard, template);
+        let window = &self.window;
+        snapshot(&shard, template, window.elapsed_secs, window.duration_secs, self.active_vus)
+    }
+}
+
+impl ClosedWindow {
+    fn new(window: &Shard, elapsed_secs: f64, duration_secs: f64) -> Self {
+        let steps = window
+            .recorded()
+            .into_iter()
+            .map(|(scenario, step, stats)| {
+                let closed = ClosedStats {
+                    service: HistogramData::from_histogram(&stats.service),
+                    response: HistogramData::from_histogram(&stats.response),
+                    successes: stats.successes,
+                    failures: stats.failures,
+                };
+                (scenario.to_string(), step.to_string(), closed)
+            })
+            .collect();
+        Self { elapsed_secs, duration_secs, steps }
+    }
+
+    /// This window followed by `later`, as one
+    fn merged(&self, later: &Self) -> Self {
+        let mut steps = self.steps.clone();
+        for (scenario, step, stats) in &later.steps {
+            match steps.iter_mut().find(|(s, t, _)| (s, t) == (scenario, step)) {
+                Some((_, _, merged)) => *merged = merged.merged(stats),
+                None => steps.push((scenario.clone(), step.clone(), stats.clone())),
+            }
+        }
+        Self {
+            elapsed_secs: later.elapsed_secs,
+            duration_secs: self.duration_secs + later.duration_secs,
+            steps,
+        }
+    }
+
+    /// Add every step into `shard`, creating them from `template`
+    fn add_to(&self, shard: &mut Shard, template: &Histogram<u64>) {
+        for (scenario, step, closed) in &self.steps {
+            if let Some(stats) = shard.step_mut(scenario, step, template) {
+                closed.add_to(stats);
+            }
+        }
+    }
+}
+
+impl ClosedStats {
+    fn merged(&self, other: &Self) -> Self {
+        Self {
+            service: HistogramData::merge([&self.service, &other.service]),
+            response: HistogramData::merge([&self.response, &other.response]),
+            successes: self.successes + other.successes,
+            failures: self.failures + other.failures,
+        }
+    }
+
+    fn add_to(&self, stats: &mut StepStats) {
+        add_buckets(&mut stats.service, &self.service);
+        add_buckets(&mut stats.response, &self.response);
+        stats.successes += self.successes;
+        stats.failures += self.failures;
+    }
+}
+
+impl StepStats {
+    fn new(template: &Histogram<u64>) -> Self {
+        Self {
+            service: template.clone(),
+            response: template.clone(),
+            successes: 0,
+            failures: 0,
+            total_latency_us: 0,
+        }
+    }
+
+    fn record(&mut self, timing: &Timing) {
+        let service_us = micros(timing.service);
+        let response_us = micros(timing.response);
+        self.service.saturating_record(service_us);
+        self.total_latency_us += service_us;
+
+        let corrected = timing.expected_interval.map(micros).filter(|&interval| interval > 0);
+        match corrected {
+            Some(interval) if self.response.record_correct(response_us, interval).is_ok() => {}
+            _ => self.response.saturating_record(response_us),
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        add_histogram(&mut self.service, &other.service);
+        add_histogram(&mut self.response, &other.response);
+        self.successes += other.successes;
+        self.failures += other.failures;
+        self.total_latency_us += other.total_latency_us;
+    }
+
+    fn clear(&mut self) {
+        self.service.reset();
+        self.response.reset();
+        self.successes = 0;
+        self.failures = 0;
+        self.total_latency_us = 0;
+    }
+}
+
+/// Metrics of everything recorded in `shard`, summed up in histograms
+/// cloned from `template`
+fn snapshot(
+    shard: &Shard,
+    template: &Histogram<u64>,
+    elapsed_secs: f64,
+    duration_secs: f64,
+    active_vus: u64,
+) -> IntervalSnapshot {
+    let mut service = template.clone();
+    let mut response = template.clone();
+    let mut failed_requests = 0;
+    let mut steps = Vec::new();
+    for (scenario, step, stats) in shard.recorded() {
+        add_histogram(&mut service, &stats.service);
+        add_histogram(&mut response, &stats.response);
+        failed_requests += stats.failures;
+        steps.push(StepSummary::new(scenario, step, stats, duration_secs));
+    }
+    let total_requests = service.len();
+
+    IntervalSnapshot {
+        elapsed_secs,
+        duration_secs,
+        active_vus,
+        total_requests,
+        failed_requests,
+        requests_per_sec: rate(total_requests, duration_secs),
+        error_rate: percentage(failed_requests, total_requests),
+        service_time: LatencyDistribution::from_histogram(&service),
+        response_time: LatencyDistribution::from_histogram(&response),
+        steps,
+    }
+}
+
+/// Add `other` into `target`. Every histogram is cloned from the same
+/// template, so the ranges match and the addition cannot overflow them.
+fn add_histogram(target: &mut Histogram<u64>, other: &Histogram<u64>) {
+    if target.add(other).is_err() {
+        for value in other.iter_recorded() {
+            target.saturating_record_n(value.value_iterated_to(), value.count_at_value());
+        }
+    }
+}
+
+/// Add the recorded buckets of `data` into `target`, growing it if it
+/// auto-resizes
+fn add_buckets(target: &mut Histogram<u64>, data: &HistogramData) {
+    for &[value, count] in &data.counts {
+        if target.record_n(value, count).is_err() {
+            target.saturating_record_n(value, count);
+        }
+    }
+}
+
+/// Lock a mutex, recovering from poisoning.
+/// Poisoning means a thread panicked while holding the lock —
+/// we still want to access the data rather than propagate panic.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
+
+const fn us_to_ms(us: u64) -> u64 {
+    us / 1000
+}
+
+fn us_to_ms_f64(us: u64) -> f64 {
+    us as f64 / 1000.0
+}
+
+fn percentage(part: u64, total: u64) -> f64 {
+    if total > 0 {
+        part as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+fn rate(count: u64, duration_secs: f64) -> f64 {
+    if duration_secs > 0.0 {
+        count as f64 / duration_secs
+    } else {
+        0.0
+    }
+}
+
+fn mean_ms(total_us: u64, count: u64) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total_us as f64 / count as f64 / 1000.0
     }
-    let index = ((p / 100.0) * sorted_data.len() as f64) as usize;
-    let index = index.min(sorted_data.len() - 1);
-    sorted_data[index]
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_collector_basic() {
-        let collector = SimpleCollector::new();
-        collector.record_success("test_step", Duration::from_millis(100), 1024, 2048);
+        let collector = HdrCollector::new().unwrap();
+        collector.record_success("default", "test_step", Duration::from_millis(100), 1024, 2048);
 
         let summary = collector.summary();
         assert_eq!(summary.total_requests, 1);
@@ -196,13 +948,255 @@ mod tests {
 
     #[test]
     fn test_collector_percentiles() {
-        let collector = SimpleCollector::new();
+        let collector = HdrCollector::new().unwrap();
         for i in 0..100 {
-            collector.record_success("test", Duration::from_millis(i), 0, 0);
+            collector.record_success("default", "test", Duration::from_millis(i), 0, 0);
         }
 
         let summary = collector.summary();
         assert!(summary.p50_latency_ms >= 45 && summary.p50_latency_ms <= 55);
         assert!(summary.p95_latency_ms >= 90);
     }
+
+    #[test]
+    fn test_collector_scenario_breakdown() {
+        let collector = HdrCollector::new().unwrap();
+        collector.record_success("browsing", "GET", Duration::from_millis(10), 0, 0);
+        collector.record_success("browsing", "GET", Duration::from_millis(30), 0, 0);
+        collector.record_failure("checkout", "POST", "timeout", Duration::from_millis(50));
+
+        let summary = collector.summary();
+        let browsing = &summary.scenarios["browsing"];
+        assert_eq!(browsing.total_requests, 2);
+        assert!((browsing.avg_latency_ms - 20.0).abs() < f64::EPSILON);
+        assert_eq!(summary.scenarios["checkout"].failed_requests, 1);
+    }
+
+    #[test]
+    fn test_step_breakdown() {
+        let collector = HdrCollector::new().unwrap();
+        for i in 1..=1000 {
+            collector.record_success("shop", "search", Duration::from_micros(i * 100), 0, 0);
+        }
+        collector.record_success("shop", "cart", Duration::from_micros(1500), 0, 0);
+        collector.record_failure("shop", "cart", "timeout", Duration::from_millis(30));
+
+        let summary = collector.summary();
+        let names: Vec<_> = summary.steps.iter().map(|s| s.step.as_str()).collect();
+        assert_eq!(names, ["cart", "search"]);
+
+        let cart = &summary.steps[0];
+        assert_eq!((cart.total_requests, cart.failed_requests), (2, 1));
+        assert!((cart.service_time.min_ms - 1.5).abs() < 0.01);
+        assert!((cart.service_time.max_ms - 30.0).abs() < 0.05);
+
+        let search = &summary.steps[1];
+        let latency = &search.service_time;
+        assert_eq!(search.successful_requests, 1000);
+        assert!((latency.p50_ms - 50.0).abs() < 0.1, "{}", latency.p50_ms);
+        assert!((latency.p75_ms - 75.0).abs() < 0.1);
+        assert!((latency.p90_ms - 90.0).abs() < 0.1);
+        assert!((latency.p99_ms - 99.0).abs() < 0.1);
+        assert!((latency.p999_ms - 99.9).abs() < 0.1);
+        assert!((latency.avg_ms - 50.05).abs() < 0.1);
+        assert_eq!(search.response_time, search.service_time);
+        assert!(search.requests_per_sec > 0.0);
+    }
+
+    #[test]
+    fn test_interval_snapshots() {
+        let collector = HdrCollector::new().unwrap();
+        let vu = collector.track_vu();
+        for _ in 0..10 {
+            collector.record_success("default", "GET", Duration::from_millis(10), 0, 0);
+        }
+        collector.record_failure("default", "POST", "boom", Duration::from_millis(100));
+        let first = collector.record_interval();
+        assert_eq!(first.active_vus, 1);
+        assert_eq!((first.total_requests, first.failed_requests), (11, 1));
+        assert!((first.error_rate - 100.0 / 11.0).abs() < 1e-9);
+        assert!((first.service_time.max_ms - 100.0).abs() < 0.1);
+        assert_eq!(first.steps.len(), 2);
+
+        // A window only holds what completed since the previous one
+        drop(vu);
+        collector.record_success("default", "GET", Duration::from_millis(2), 0, 0);
+        let second = collector.record_interval();
+        assert_eq!(second.active_vus, 0);
+        assert_eq!((second.total_requests, second.failed_requests), (1, 0));
+        assert!((second.response_time.p99_ms - 2.0).abs() < 0.01);
+        assert_eq!(second.steps.len(), 1);
+        assert!(second.elapsed_secs >= first.elapsed_secs);
+
+        let empty = collector.record_interval();
+        assert_eq!(empty.total_requests, 0);
+        assert!(empty.steps.is_empty());
+
+        let summary = collector.summary();
+        assert_eq!(summary.total_requests, 12);
+        assert_eq!(summary.errors_by_type["boom"], 1);
+        assert_eq!(summary.timeline.len(), 3);
+    }
+
+    #[test]
+    fn test_recent_windows() {
+        let collector = HdrCollector::new().unwrap();
+        assert_eq!(collector.recent().total_requests, 0);
+        collector.keep_recent_windows(2);
+        for latency in [100, 10, 20] {
+            collector.record_success("default", "GET", Duration::from_millis(latency), 0, 0);
+            collector.record_interval();
+        }
+        collector.record_failure("default", "POST", "boom", Duration::from_millis(30));
+        let last = collector.record_interval();
+
+        // The 100ms window has rolled out
+        let recent = collector.recent();
+        assert_eq!((recent.total_requests, recent.failed_requests), (2, 1));
+        assert!((recent.service_time.max_ms - 30.0).abs() < 0.05);
+        assert!((recent.service_time.min_ms - 20.0).abs() < 0.05);
+        assert!((recent.elapsed_secs - last.elapsed_secs).abs() < f64::EPSILON);
+        assert_eq!(recent.steps.len(), 2);
+    }
+
+    #[test]
+    fn test_timeline_stays_bounded() {
+        let collector = HdrCollector::new().unwrap();
+        collector.record_success("default", "GET", Duration::from_millis(1), 0, 0);
+        collector.record_interval();
+        collector.record_failure("default", "POST", "boom", Duration::from_millis(3));
+        collector.record_interval();
+        let [fast, failed] = timeline_points(&collector);
+
+        let mut aggregate = Aggregate::new();
+        for i in 0..2 * MAX_TIMELINE_POINTS + 501 {
+            aggregate.push_point(if i % 2 == 0 { fast.clone() } else { failed.clone() });
+            assert!(aggregate.timeline.len() <= MAX_TIMELINE_POINTS);
+        }
+
+        // Every window is still counted once; points now cover 4 windows
+        let template = &collector.inner.sparse_template;
+        let timeline: Vec<_> =
+            aggregate.timeline.iter().map(|point| point.snapshot(template)).collect();
+        assert_eq!(timeline.len(), 626);
+        assert_eq!(timeline.iter().map(|point| point.total_requests).sum::<u64>(), 2501);
+        let point = &timeline[0];
+        assert_eq!((point.total_requests, point.failed_requests), (4, 2));
+        assert!((point.error_rate - 50.0).abs() < 1e-9);
+        assert!((point.service_time.min_ms - 1.0).abs() < 0.01);
+        assert!((point.service_time.max_ms - 3.0).abs() < 0.01);
+        assert!((point.service_time.avg_ms - 2.0).abs() < 0.01);
+        let names: Vec<_> = point.steps.iter().map(|step| step.step.as_str()).collect();
+        assert_eq!(names, ["GET", "POST"]);
+        assert_eq!(timeline.last().unwrap().total_requests, 1);
+    }
+
+    #[test]
+    fn test_merged_points_keep_latency_spikes() {
+        let collector = HdrCollector::new().unwrap();
+        for _ in 0..20 {
+            collector.record_success("default", "GET", Duration::from_millis(500), 0, 0);
+        }
+        collector.record_interval();
+        for _ in 0..980 {
+            collector.record_success("default", "GET", Duration::from_millis(10), 0, 0);
+        }
+        collector.record_interval();
+        let [spike, calm] = timeline_points(&collector);
+        let template = &collector.inner.sparse_template;
+        assert!((spike.snapshot(template).service_time.p99_ms - 500.0).abs() < 0.5);
+        assert!((calm.snapshot(template).service_time.p99_ms - 10.0).abs() < 0.01);
+
+        // The slowest 2% of the merged window is the spike, so its p99 is
+        // too; averaging the windows' p99 by request count would give 19.8ms
+        let merged = spike.merged(&calm).snapshot(template);
+        assert_eq!(merged.total_requests, 1000);
+        assert!((merged.service_time.p99_ms - 500.0).abs() < 0.5);
+        assert!((merged.response_time.p99_ms - 500.0).abs() < 0.5);
+        assert!((merged.service_time.p50_ms - 10.0).abs() < 0.01);
+        assert!((merged.steps[0].service_time.p99_ms - 500.0).abs() < 0.5);
+    }
+
+    fn timeline_points<const N: usize>(collector: &HdrCollector) -> [TimelinePoint; N] {
+        lock(&collector.inner.aggregate).timeline.clone().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_service_and_response_time() {
+        let collector = HdrCollector::new().unwrap();
+        // Started 400ms late, then took 100ms
+        let late = Timing {
+            service: Duration::from_millis(100),
+            response: Duration::from_millis(500),
+            expected_interval: None,
+        };
+        collector.record_success("default", "GET", late, 0, 0);
+
+        let summary = collector.summary();
+        assert_eq!(summary.max_latency_ms, 100);
+        assert!((summary.service_time.max_ms - 100.0).abs() < 0.1);
+        assert!((summary.response_time.max_ms - 500.0).abs() < 0.5);
+        assert!((summary.steps[0].response_time.p50_ms - 500.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_expected_interval_back_fills_response_time() {
+        let collector = HdrCollector::new().unwrap();
+        let stalled = Timing {
+            expected_interval: Some(Duration::from_millis(100)),
+            ..Duration::from_millis(500).into()
+        };
+        collector.record_success("default", "GET", stalled, 0, 0);
+
+        // 500ms stall with a 100ms pace hides requests that would have
+        // waited 400, 300, 200 and 100ms
+        let summary = collector.summary();
+        assert_eq!(summary.total_requests, 1);
+        let response = summary.response_time;
+        assert!((response.min_ms - 100.0).abs() < 0.1);
+        assert!((response.avg_ms - 300.0).abs() < 0.5);
+        assert!((summary.service_time.min_ms - 500.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_microsecond_resolution() {
+        let collector = HdrCollector::new().unwrap();
+        collector.record_success("default", "fast", Duration::from_micros(250), 0, 0);
+        collector.record_success("default", "fast", Duration::from_micros(750), 0, 0);
+
+        let summary = collector.summary();
+        assert!((summary.avg_latency_ms - 0.5).abs() < f64::EPSILON);
+        assert_eq!(summary.max_latency_ms, 0);
+    }
+
+    #[test]
+    fn test_merges_shards_from_every_thread() {
+        let collector = HdrCollector::new().unwrap();
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let collector = collector.clone();
+                std::thread::spawn(move || {
+                    for i in 0..250 {
+                        collector.record_success("default", "step", Duration::from_millis(i), 1, 1);
+                    }
+                    collector.record_failure("default", "step", "boom", Duration::from_millis(1));
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        // Merging twice must not double count
+        collector.merge();
+        let summary = collector.summary();
+        assert_eq!(summary.total_requests, 1004);
+        assert_eq!(summary.total_bytes_sent, 1000);
+        assert_eq!(summary.errors_by_type["boom"], 4);
+        assert_eq!(summary.scenarios["default"].total_requests, 1004);
+        assert!(summary.p99_latency_ms >= 245);
+
+        collector.reset();
+        assert_eq!(collector.summary().total_requests, 0);
+    }
 }
@@ -0,0 +1,206 @@
+use crate::collector::LatencyDistribution;
+use crate::error::{MetricsError, Result};
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Serializable copy of a latency histogram.
+///
+/// Only the recorded buckets are kept, so the size depends on how spread
+/// out the latencies are rather than on the run length. Rebuilding it with
+/// [`to_histogram`](Self::to_histogram) gives back the same percentiles.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistogramData {
+    /// Precision the values were recorded with
+    pub significant_digits: u8,
+    /// `[value in microseconds, count]` for every non-empty bucket,
+    /// lowest value first
+    pub counts: Vec<[u64; 2]>,
+}
+
+/// Service and response time histograms of one step
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepHistogram {
+    pub scenario: String,
+    pub step: String,
+    pub service: HistogramData,
+    pub response: HistogramData,
+}
+
+impl HistogramData {
+    pub(crate) fn from_histogram(histogram: &Histogram<u64>) -> Self {
+        Self {
+            significant_digits: histogram.sigfig(),
+            counts: histogram
+                .iter_recorded()
+                .map(|bucket| [bucket.value_iterated_to(), bucket.count_at_value()])
+                .collect(),
+        }
+    }
+
+    /// Combine several histograms into one, e.g. every step of a run
+    pub fn merge<'a>(histograms: impl IntoIterator<Item = &'a Self>) -> Self {
+        let mut significant_digits = 0;
+        let mut merged = BTreeMap::new();
+        for histogram in histograms {
+            significant_digits = significant_digits.max(histogram.significant_digits);
+            for &[value, count] in &histogram.counts {
+                *merged.entry(value).or_insert(0) += count;
+            }
+        }
+        Self { significant_digits, counts: merged.into_iter().map(<[u64; 2]>::from).collect() }
+    }
+
+    /// Rebuild the histogram, e.g. to merge runs or compute other quantiles
+    pub fn to_histogram(&self) -> Result<Histogram<u64>> {
+        let mut histogram = Histogram::new(self.significant_digits)
+            .map_err(|e| MetricsError::HistogramError(e.to_string()))?;
+        for &[value, count] in &self.counts {
+            histogram
+                .record_n(value, count)
+                .map_err(|e| MetricsError::HistogramError(e.to_string()))?;
+        }
+        Ok(histogram)
+    }
+
+    /// Percentiles of the recorded latencies
+    pub fn distribution(&self) -> Result<LatencyDistribution> {
+        Ok(LatencyDistribution::from_histogram(&self.to_histogram()?))
+    }
+
+    /// Number of recorded values
+    pub fn len(&self) -> u64 {
+        self.counts.iter().map(|[_, count]| count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+/// One-sided Mann–Whitney U test of whether `candidate` latencies tend to
+/// be higher than `baseline` ones.
+///
+/// Returns the p-value: the chance of seeing a shift at least this large
+/// if both runs had the same distribution. Uses the normal approximation
+/// with tie correction, which is accurate for the sample sizes of a load
+/// test. `None` when either side is empty or every value is the same.
+pub fn mann_whitney_p_value(baseline: &HistogramData, candidate: &HistogramData) -> Option<f64> {
+    let (n_baseline, n_candidate) = (baseline.len() as f64, candidate.len() as f64);
+    if n_baseline == 0.0 || n_candidate == 0.0 {
+        return None;
+    }
+
+    // Value → (baseline count, candidate count), ascending
+    let mut values: BTreeMap<u64, (u64, u64)> = BTreeMap::new();
+    for &[value, count] in &baseline.counts {
+        values.entry(value).or_default().0 += count;
+    }
+    for &[value, count] in &candidate.counts {
+        values.entry(value).or_default().1 += count;
+    }
+
+    let mut ranked = 0.0;
+    let mut candidate_rank_sum = 0.0;
+    let mut tie_correction = 0.0;
+    for (in_baseline, in_candidate) in values.into_values() {
+        let group = (in_baseline + in_candidate) as f64;
+        let mean_rank = ranked + (group + 1.0) / 2.0;
+        candidate_rank_sum += in_candidate as f64 * mean_rank;
+        tie_correction += group.powi(3) - group;
+        ranked += group;
+    }
+
+    let n = n_baseline + n_candidate;
+    let u = (-n_candidate).mul_add((n_candidate + 1.0) / 2.0, candidate_rank_sum);
+    let mean = n_baseline * n_candidate / 2.0;
+    let variance = n_baseline * n_candidate / 12.0 * ((n + 1.0) - tie_correction / (n * (n - 1.0)));
+    if variance <= 0.0 || !variance.is_finite() {
+        return None;
+    }
+    // Continuity correction towards the mean
+    let z = (u - mean - 0.5) / variance.sqrt();
+    Some(0.5 * erfc(z / std::f64::consts::SQRT_2))
+}
+
+/// Complementary error function, accurate to about 1e-7 (Numerical
+/// Recipes' Chebyshev fit)
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / 0.5f64.mul_add(z, 1.0);
+    let poly = [
+        -1.265_512_23,
+        1.000_023_68,
+        0.374_091_96,
+        0.096_784_18,
+        -0.186_288_06,
+        0.278_868_07,
+        -1.135_203_98,
+        1.488_515_87,
+        -0.822_152_23,
+        0.170_872_77,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |acc: f64, &c| acc.mul_add(t, c));
+    let result = t * (-z).mul_add(z, poly).exp();
+    if x >= 0.0 {
+        result
+    } else {
+        2.0 - result
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::HdrCollector;
+    use std::time::Duration;
+
+    #[test]
+    fn test_histogram_round_trip() {
+        let collector = HdrCollector::new().unwrap();
+        for ms in 1..=200 {
+            collector.record_success("shop", "Browse", Duration::from_micros(ms * 1013), 0, 0);
+        }
+        let summary = collector.summary();
+        let recorded = &summary.histograms[0];
+        assert_eq!((recorded.scenario.as_str(), recorded.step.as_str()), ("shop", "Browse"));
+        assert_eq!(recorded.service.len(), 200);
+
+        let json = serde_json::to_string(recorded).unwrap();
+        let restored: StepHistogram = serde_json::from_str(&json).unwrap();
+        assert_eq!(&restored, recorded);
+        assert_eq!(restored.service.distribution().unwrap(), summary.steps[0].service_time);
+        assert!(HistogramData::default().distribution().unwrap().max_ms.abs() < f64::EPSILON);
+    }
+
+    fn uniform(from: u64, to: u64) -> HistogramData {
+        HistogramData {
+            significant_digits: 3,
+            counts: (from..to).map(|value| [value, 1]).collect(),
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let merged = HistogramData::merge([&uniform(0, 3), &uniform(2, 4)]);
+        assert_eq!(merged.counts, [[0, 1], [1, 1], [2, 2], [3, 1]]);
+        assert_eq!(merged.len(), 5);
+    }
+
+    #[test]
+    fn test_mann_whitney() {
+        let baseline = uniform(1000, 1200);
+        // A clear shift is significant, the same distribution is not
+        assert!(mann_whitney_p_value(&baseline, &uniform(1100, 1300)).unwrap() < 0.001);
+        let same = mann_whitney_p_value(&baseline, &baseline).unwrap();
+        assert!((0.4..0.6).contains(&same), "{same}");
+        // Faster is not a regression
+        assert!(mann_whitney_p_value(&baseline, &uniform(900, 1100)).unwrap() > 0.999);
+        assert_eq!(mann_whitney_p_value(&baseline, &HistogramData::default()), None);
+        assert_eq!(mann_whitney_p_value(&uniform(5, 6), &uniform(5, 6)), None);
+        assert!((erfc(0.0) - 1.0).abs() < 1e-7 && (erfc(1.0) - 0.157_299_2).abs() < 1e-6);
+    }
+}
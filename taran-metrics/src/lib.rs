@@ -1,5 +1,12 @@
 pub mod collector;
 pub mod error;
+pub mod histogram;
+pub mod threshold;
 
-pub use collector::{MetricsSummary, SimpleCollector};
+pub use collector::{
+    ActiveVu, Counters, HdrCollector, IntervalSnapshot, LatencyDistribution, MetricsSummary,
+    ScenarioSummary, StepSummary, Timing,
+};
 pub use error::{MetricsError, Result};
+pub use histogram::{mann_whitney_p_value, HistogramData, StepHistogram};
+pub use threshold::ThresholdOutcome;
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// Result of one threshold rule evaluated against the run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdOutcome {
+    /// The rule as written in the scenario
+    pub expression: String,
+    pub passed: bool,
+    /// Observed value in `unit`; `None` when the metric was never recorded
+    pub actual: Option<f64>,
+    pub unit: String,
+    /// The rule failed during the run and stopped it early; `actual` is
+    /// then the value of the window that breached it
+    #[serde(default)]
+    pub aborted: bool,
+}
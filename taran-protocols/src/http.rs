@@ -25,6 +25,8 @@ pub struct HttpRequest {
 pub struct HttpResponse {
     pub status: u16,
     pub headers: HashMap<String, String>,
+    /// Cookies set by the response (`Set-Cookie` name → value)
+    pub cookies: HashMap<String, String>,
     pub body: String,
     pub duration: Duration,
     pub bytes_sent: u64,
@@ -32,9 +34,23 @@ pub struct HttpResponse {
 }
 
 impl HttpClient {
-    /// Create a new HTTP client
+    /// Create a new HTTP client with its own connection pool
     pub fn new() -> Result<Self> {
-        let client = Client::builder()
+        Self::build(Client::builder())
+    }
+
+    /// Create a client that opens a new connection for every request
+    pub fn without_keep_alive() -> Result<Self> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::CONNECTION,
+            reqwest::header::HeaderValue::from_static("close"),
+        );
+        Self::build(Client::builder().pool_max_idle_per_host(0).default_headers(headers))
+    }
+
+    fn build(builder: reqwest::ClientBuilder) -> Result<Self> {
+        let client = builder
             .timeout(Duration::from_secs(30))
             .build()
             .map_err(|e| ProtocolError::ConnectionError(e.to_string()))?;
@@ -74,8 +90,15 @@ impl HttpClient {
 
         // Extract headers
         let mut headers = HashMap::new();
+        let mut cookies = HashMap::new();
         for (key, value) in response.headers() {
             if let Ok(value_str) = value.to_str() {
+                // Set-Cookie may repeat, so collect every occurrence separately
+                if key == reqwest::header::SET_COOKIE {
+                    if let Some((name, value)) = parse_set_cookie(value_str) {
+                        cookies.insert(name, value);
+                    }
+                }
                 headers.insert(key.to_string(), value_str.to_string());
             }
         }
@@ -92,7 +115,7 @@ impl HttpClient {
 
         let duration = start.elapsed();
 
-        Ok(HttpResponse { status, headers, body, duration, bytes_sent, bytes_received })
+        Ok(HttpResponse { status, headers, cookies, body, duration, bytes_sent, bytes_received })
     }
 
     /// Convenience method for GET request
@@ -139,6 +162,23 @@ impl HttpResponse {
     pub fn header(&self, key: &str) -> Option<&String> {
         self.headers.get(key)
     }
+
+    /// Get a cookie set by this response
+    pub fn cookie(&self, name: &str) -> Option<&String> {
+        self.cookies.get(name)
+    }
+}
+
+/// Split a `Set-Cookie` header into the cookie's name and value,
+/// ignoring attributes such as `Path` or `Expires`
+fn parse_set_cookie(header: &str) -> Option<(String, String)> {
+    let pair = header.split(';').next()?;
+    let (name, value) = pair.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some((name.to_string(), value.trim().trim_matches('"').to_string()))
 }
 
 fn parse_method(method: &str) -> Result<Method> {
@@ -188,6 +228,16 @@ mod tests {
         assert!(parse_method("INVALID").is_err());
     }
 
+    #[test]
+    fn test_parse_set_cookie() {
+        assert_eq!(
+            parse_set_cookie("session=abc123; Path=/; HttpOnly"),
+            Some(("session".to_string(), "abc123".to_string()))
+        );
+        assert_eq!(parse_set_cookie("flag=\"on\""), Some(("flag".to_string(), "on".to_string())));
+        assert_eq!(parse_set_cookie("invalid"), None);
+    }
+
     #[test]
     fn test_estimate_request_size() {
         let req = HttpRequest {
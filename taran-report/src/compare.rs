@@ -0,0 +1,437 @@
+use crate::json::RunReport;
+use std::fmt::Write;
+use taran_metrics::{mann_whitney_p_value, HistogramData, LatencyDistribution, StepSummary};
+
+/// Name of the row comparing whole runs
+const TOTAL: &str = "(all steps)";
+
+/// How much worse a candidate may be before it counts as a regression
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerances {
+    /// Allowed latency increase, in percent of the baseline
+    pub latency_pct: f64,
+    /// Allowed throughput decrease, in percent of the baseline
+    pub rps_pct: f64,
+    /// Allowed error-rate increase, in percentage points
+    pub error_rate_points: f64,
+    /// Significance level of the latency test; a latency increase beyond
+    /// the tolerance is only a regression when the test's p-value is below it
+    pub alpha: f64,
+}
+
+impl Default for Tolerances {
+    fn default() -> Self {
+        Self { latency_pct: 10.0, rps_pct: 10.0, error_rate_points: 1.0, alpha: 0.05 }
+    }
+}
+
+/// Two runs lined up step by step
+#[derive(Debug, Clone)]
+pub struct RunComparison {
+    pub baseline: String,
+    pub candidate: String,
+    pub tolerances: Tolerances,
+    /// Whole-run row first, then every step present in both runs
+    pub steps: Vec<StepComparison>,
+    /// Steps, as `scenario/step`, that ran in only one of the runs
+    pub only_in_baseline: Vec<String>,
+    pub only_in_candidate: Vec<String>,
+}
+
+/// Metrics of one step in both runs
+#[derive(Debug, Clone)]
+pub struct StepComparison {
+    /// `scenario/step`, or `(all steps)` for the whole run
+    pub name: String,
+    pub metrics: Vec<MetricDelta>,
+    /// One-sided Mann–Whitney p-value that the candidate's response times
+    /// are higher; `None` without histograms on both sides
+    pub p_value: Option<f64>,
+}
+
+/// One metric in both runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricDelta {
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub candidate: f64,
+    pub regression: bool,
+}
+
+/// Figures compared for each step
+struct Side<'a> {
+    response_time: &'a LatencyDistribution,
+    requests: u64,
+    failed: u64,
+    requests_per_sec: f64,
+    histogram: HistogramData,
+}
+
+impl RunComparison {
+    pub fn new(baseline: &RunReport, candidate: &RunReport, tolerances: Tolerances) -> Self {
+        let mut steps =
+            vec![compare(TOTAL, &Side::run(baseline), &Side::run(candidate), &tolerances)];
+        let mut only_in_baseline = Vec::new();
+
+        for old in &baseline.summary.steps {
+            let name = format!("{}/{}", old.scenario, old.step);
+            let Some(new) = candidate
+                .summary
+                .steps
+                .iter()
+                .find(|new| new.scenario == old.scenario && new.step == old.step)
+            else {
+                only_in_baseline.push(name);
+                continue;
+            };
+            steps.push(compare(
+                &name,
+                &Side::step(baseline, old),
+                &Side::step(candidate, new),
+                &tolerances,
+            ));
+        }
+
+        let only_in_candidate = candidate
+            .summary
+            .steps
+            .iter()
+            .filter(|new| {
+                !baseline
+                    .summary
+                    .steps
+                    .iter()
+                    .any(|old| old.scenario == new.scenario && old.step == new.step)
+            })
+            .map(|new| format!("{}/{}", new.scenario, new.step))
+            .collect();
+
+        Self {
+            baseline: baseline.run.scenario.clone(),
+            candidate: candidate.run.scenario.clone(),
+            tolerances,
+            steps,
+            only_in_baseline,
+            only_in_candidate,
+        }
+    }
+
+    /// Whether any metric of any step regressed
+    pub fn has_regression(&self) -> bool {
+        self.steps.iter().flat_map(|step| &step.metrics).any(|metric| metric.regression)
+    }
+
+    /// Aligned plain-text tables, one per step
+    pub fn console(&self) -> String {
+        let mut out =
+            format!("Comparing baseline '{}' with candidate '{}'\n", self.baseline, self.candidate);
+        let _ = writeln!(out, "{}\n", self.tolerance_line());
+        for step in &self.steps {
+            let _ = writeln!(out, "{}{}", step.name, significance(step.p_value, self.tolerances));
+            let _ = writeln!(
+                out,
+                "  {:<14}  {:>10}  {:>10}  {:>10}  {:>8}",
+                "Metric", "Baseline", "Candidate", "Delta", "Delta %"
+            );
+            for metric in &step.metrics {
+                let _ = writeln!(
+                    out,
+                    "  {:<14}  {:>10.2}  {:>10.2}  {:>+10.2}  {:>8}{}",
+                    metric.metric,
+                    metric.baseline,
+                    metric.candidate,
+                    metric.delta(),
+                    percent(metric.delta_pct()),
+                    if metric.regression { "  REGRESSION" } else { "" }
+                );
+            }
+            out.push('\n');
+        }
+        out.push_str(&self.missing_steps());
+        out.push_str(&self.verdict());
+        out.push('\n');
+        out
+    }
+
+    /// One Markdown table for a pull-request comment
+    pub fn markdown(&self) -> String {
+        let mut md = format!("## Load test comparison — {}\n\n", self.verdict());
+        let _ = writeln!(
+            md,
+            "Baseline `{}`, candidate `{}`. {}\n",
+            self.baseline,
+            self.candidate,
+            self.tolerance_line()
+        );
+        md.push_str("| Step | Metric | Baseline | Candidate | Δ | Δ % | |\n");
+        md.push_str("|---|---|---:|---:|---:|---:|---|\n");
+        for step in &self.steps {
+            for (i, metric) in step.metrics.iter().enumerate() {
+                let name = if i == 0 {
+                    format!("{}{}", step.name, significance(step.p_value, self.tolerances))
+                } else {
+                    String::new()
+                };
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {:.2} | {:.2} | {:+.2} | {} | {} |",
+                    name.replace('|', "\\|"),
+                    metric.metric,
+                    metric.baseline,
+                    metric.candidate,
+                    metric.delta(),
+                    percent(metric.delta_pct()),
+                    if metric.regression { "❌ regression" } else { "" }
+                );
+            }
+        }
+        let missing = self.missing_steps();
+        if !missing.is_empty() {
+            md.push('\n');
+            md.push_str(&missing);
+        }
+        md
+    }
+
+    fn tolerance_line(&self) -> String {
+        let t = self.tolerances;
+        format!(
+            "Tolerances: latency +{}%, RPS -{}%, error rate +{} points, significance p < {}.",
+            t.latency_pct, t.rps_pct, t.error_rate_points, t.alpha
+        )
+    }
+
+    fn missing_steps(&self) -> String {
+        let mut out = String::new();
+        if !self.only_in_baseline.is_empty() {
+            let _ = writeln!(out, "Only in baseline: {}", self.only_in_baseline.join(", "));
+        }
+        if !self.only_in_candidate.is_empty() {
+            let _ = writeln!(out, "Only in candidate: {}", self.only_in_candidate.join(", "));
+        }
+        out
+    }
+
+    fn verdict(&self) -> String {
+        let regressed: Vec<_> = self
+            .steps
+            .iter()
+            .filter(|step| step.metrics.iter().any(|metric| metric.regression))
+            .map(|step| step.name.as_str())
+            .collect();
+        if regressed.is_empty() {
+            "no regression".to_string()
+        } else {
+            format!("regression in {}", regressed.join(", "))
+        }
+    }
+}
+
+impl<'a> Side<'a> {
+    /// The whole run, with the response-time histograms of every step merged
+    fn run(report: &'a RunReport) -> Self {
+        let summary = &report.summary;
+        Self {
+            response_time: &summary.response_time,
+            requests: summary.total_requests,
+            failed: summary.failed_requests,
+            requests_per_sec: summary.requests_per_sec,
+            histogram: HistogramData::merge(summary.histograms.iter().map(|h| &h.response)),
+        }
+    }
+
+    fn step(report: &RunReport, step: &'a StepSummary) -> Self {
+        let histogram = report
+            .summary
+            .histograms
+            .iter()
+            .find(|h| h.scenario == step.scenario && h.step == step.step)
+            .map(|h| h.response.clone())
+            .unwrap_or_default();
+        Self {
+            response_time: &step.response_time,
+            requests: step.total_requests,
+            failed: step.failed_requests,
+            requests_per_sec: step.requests_per_sec,
+            histogram,
+        }
+    }
+}
+
+impl MetricDelta {
+    pub fn delta(&self) -> f64 {
+        self.candidate - self.baseline
+    }
+
+    /// Change relative to the baseline; `None` when the baseline is zero
+    pub fn delta_pct(&self) -> Option<f64> {
+        (self.baseline != 0.0).then(|| self.delta() / self.baseline * 100.0)
+    }
+}
+
+fn compare(
+    name: &str,
+    baseline: &Side<'_>,
+    candidate: &Side<'_>,
+    tolerances: &Tolerances,
+) -> StepComparison {
+    let p_value = mann_whitney_p_value(&baseline.histogram, &candidate.histogram);
+    // Without histograms fall back to comparing the raw numbers
+    let significant = p_value.map_or(true, |p| p < tolerances.alpha);
+    let latency = |metric, value: fn(&LatencyDistribution) -> f64| {
+        let mut delta = MetricDelta {
+            metric,
+            baseline: value(baseline.response_time),
+            candidate: value(candidate.response_time),
+            regression: false,
+        };
+        delta.regression =
+            significant && delta.delta_pct().is_some_and(|pct| pct > tolerances.latency_pct);
+        delta
+    };
+
+    let mut rps = MetricDelta {
+        metric: "RPS",
+        baseline: baseline.requests_per_sec,
+        candidate: candidate.requests_per_sec,
+        regression: false,
+    };
+    rps.regression = rps.delta_pct().is_some_and(|pct| -pct > tolerances.rps_pct);
+
+    let mut error_rate = MetricDelta {
+        metric: "Error rate %",
+        baseline: error_rate_pct(baseline),
+        candidate: error_rate_pct(candidate),
+        regression: false,
+    };
+    error_rate.regression = error_rate.delta() > tolerances.error_rate_points;
+
+    StepComparison {
+        name: name.to_string(),
+        metrics: vec![
+            latency("p50 (ms)", |d| d.p50_ms),
+            latency("p90 (ms)", |d| d.p90_ms),
+            latency("p95 (ms)", |d| d.p95_ms),
+            latency("p99 (ms)", |d| d.p99_ms),
+            rps,
+            error_rate,
+        ],
+        p_value,
+    }
+}
+
+fn error_rate_pct(side: &Side<'_>) -> f64 {
+    if side.requests == 0 {
+        0.0
+    } else {
+        side.failed as f64 / side.requests as f64 * 100.0
+    }
+}
+
+fn percent(value: Option<f64>) -> String {
+    value.map_or_else(|| "n/a".to_string(), |pct| format!("{pct:+.1}%"))
+}
+
+/// Suffix describing the latency test of a step
+fn significance(p_value: Option<f64>, tolerances: Tolerances) -> String {
+    match p_value {
+        Some(p) if p < tolerances.alpha => format!(" (slower, p = {p:.3})"),
+        Some(p) => format!(" (not significantly slower, p = {p:.3})"),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::json::RunMetadata;
+    use chrono::Utc;
+    use std::time::Duration;
+    use taran_metrics::HdrCollector;
+
+    /// A run of 200 `Browse` and `extra` requests with latencies from
+    /// `from_ms` upwards, and `failures` failed `Login` requests
+    fn run(from_ms: u64, failures: u64, extra: &str) -> RunReport {
+        let collector = HdrCollector::new().unwrap();
+        for i in 0..200 {
+            let latency = Duration::from_micros(from_ms * 1000 + i * 50);
+            collector.record_success("shop", "Browse", latency, 0, 0);
+            collector.record_success("shop", extra, latency, 0, 0);
+        }
+        collector.record_success("shop", "Login", Duration::from_millis(5), 0, 0);
+        for _ in 0..failures {
+            collector.record_failure("shop", "Login", "HTTP 500", Duration::from_millis(5));
+        }
+        let run = RunMetadata {
+            scenario: "Smoke".to_string(),
+            description: None,
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+        };
+        // The collector's clock makes throughput vary between runs
+        let mut summary = collector.summary();
+        summary.requests_per_sec = 100.0;
+        for step in &mut summary.steps {
+            step.requests_per_sec = 100.0;
+        }
+        RunReport::new(run, &(), summary).unwrap()
+    }
+
+    fn step<'a>(comparison: &'a RunComparison, name: &str) -> &'a StepComparison {
+        comparison.steps.iter().find(|step| step.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_same_run_has_no_regression() {
+        let comparison =
+            RunComparison::new(&run(10, 0, "Cart"), &run(10, 0, "Cart"), Tolerances::default());
+        assert!(!comparison.has_regression());
+        assert_eq!(comparison.steps[0].name, TOTAL);
+        assert_eq!(comparison.steps.len(), 4);
+        assert!(comparison.console().contains("no regression"));
+    }
+
+    #[test]
+    fn test_detects_regressions() {
+        let comparison =
+            RunComparison::new(&run(10, 0, "Cart"), &run(20, 1, "Search"), Tolerances::default());
+        assert!(comparison.has_regression());
+
+        let browse = step(&comparison, "shop/Browse");
+        assert!(browse.p_value.unwrap() < 0.001);
+        let p50 = &browse.metrics[0];
+        assert!(p50.regression && p50.delta_pct().unwrap() > 50.0);
+
+        let login = step(&comparison, "shop/Login");
+        let error_rate = login.metrics.iter().find(|m| m.metric == "Error rate %").unwrap();
+        assert!(error_rate.regression);
+        assert!((error_rate.candidate - 50.0).abs() < 1e-9);
+
+        assert_eq!(comparison.only_in_baseline, ["shop/Cart"]);
+        assert_eq!(comparison.only_in_candidate, ["shop/Search"]);
+
+        let md = comparison.markdown();
+        assert!(md.contains("regression in (all steps), shop/Browse, shop/Login"));
+        assert!(md.contains("❌ regression"));
+        assert!(comparison.console().contains("REGRESSION"));
+    }
+
+    #[test]
+    fn test_insignificant_shift_is_not_a_regression() {
+        // p99 moved beyond the tolerance but the distributions are the same
+        let mut candidate = run(10, 0, "Cart");
+        candidate.summary.steps[0].response_time.p99_ms *= 1.5;
+        let comparison = RunComparison::new(&run(10, 0, "Cart"), &candidate, Tolerances::default());
+        let browse = step(&comparison, "shop/Browse");
+        assert!(browse.metrics[3].delta_pct().unwrap() > 40.0);
+        assert!(browse.p_value.unwrap() > 0.4);
+        assert!(!comparison.has_regression());
+
+        // Without histograms the point comparison decides
+        candidate.summary.histograms.clear();
+        let comparison = RunComparison::new(&run(10, 0, "Cart"), &candidate, Tolerances::default());
+        assert_eq!(step(&comparison, "shop/Browse").p_value, None);
+        assert!(step(&comparison, "shop/Browse").metrics[3].regression);
+    }
+}
@@ -1,7 +1,12 @@
 use crate::error::Result;
-use taran_metrics::MetricsSummary;
+use std::fmt::Write;
+use taran_metrics::{LatencyDistribution, MetricsSummary, StepSummary, ThresholdOutcome};
 
-/// Simple console reporter that prints metrics to stdout
+/// Latency columns of the step table
+pub(crate) const LATENCY_COLUMNS: [&str; 9] =
+    ["Avg", "Min", "p50", "p75", "p90", "p95", "p99", "p99.9", "Max"];
+
+/// Plain-text summary for the terminal
 pub struct ConsoleReporter;
 
 impl ConsoleReporter {
@@ -11,43 +16,103 @@ impl ConsoleReporter {
 
     /// Print a summary of test results to the console
     pub fn print_summary(&self, summary: &MetricsSummary) -> Result<()> {
-        println!("\n{}", "=".repeat(60));
-        println!("  Test Results Summary");
-        println!("{}", "=".repeat(60));
-        println!();
-
-        println!("Requests:");
-        println!("  Total:      {}", summary.total_requests);
-        println!("  Successful: {}", summary.successful_requests);
-        println!("  Failed:     {}", summary.failed_requests);
-        println!("  Success Rate: {:.2}%", summary.success_rate);
-        println!();
-
-        println!("Latency (ms):");
-        println!("  Average: {:.2}", summary.avg_latency_ms);
-        println!("  Min:     {}", summary.min_latency_ms);
-        println!("  Max:     {}", summary.max_latency_ms);
-        println!("  p50:     {}", summary.p50_latency_ms);
-        println!("  p95:     {}", summary.p95_latency_ms);
-        println!("  p99:     {}", summary.p99_latency_ms);
-        println!();
-
-        println!("Data Transfer:");
-        println!("  Sent:     {} bytes", summary.total_bytes_sent);
-        println!("  Received: {} bytes", summary.total_bytes_received);
-        println!();
+        print!("{}", self.render(summary));
+        Ok(())
+    }
+
+    /// The summary as printed by [`print_summary`](Self::print_summary)
+    pub fn render(&self, summary: &MetricsSummary) -> String {
+        let mut out = String::new();
+        out.push('\n');
+        let _ = writeln!(out, "{}", "=".repeat(60));
+        let _ = writeln!(out, "  Test Results Summary");
+        let _ = writeln!(out, "{}", "=".repeat(60));
+        out.push('\n');
+
+        if let Some(aborted) = summary.aborted_by() {
+            let _ = writeln!(out, "Run aborted early: threshold '{}' failed", aborted.expression);
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "Requests:");
+        let _ = writeln!(out, "  Total:      {}", summary.total_requests);
+        let _ = writeln!(out, "  Successful: {}", summary.successful_requests);
+        let _ = writeln!(out, "  Failed:     {}", summary.failed_requests);
+        let _ = writeln!(out, "  Success Rate: {:.2}%", summary.success_rate);
+        let _ = writeln!(out, "  Throughput: {:.2} req/s", summary.requests_per_sec);
+        out.push('\n');
+
+        let _ = writeln!(out, "Latency (ms):  {:>10}  {:>10}", "Service", "Response");
+        let service = latency_values(&summary.service_time);
+        let response = latency_values(&summary.response_time);
+        for ((column, service), response) in LATENCY_COLUMNS.iter().zip(service).zip(response) {
+            let _ =
+                writeln!(out, "  {:<11}  {service:>10.2}  {response:>10.2}", format!("{column}:"));
+        }
+        let _ = writeln!(out, "  Service: actual start to completion");
+        let _ = writeln!(
+            out,
+            "  Response: scheduled start to completion (coordinated omission corrected)"
+        );
+        out.push('\n');
+
+        let _ = writeln!(out, "Data Transfer:");
+        let _ = writeln!(out, "  Sent:     {} bytes", summary.total_bytes_sent);
+        let _ = writeln!(out, "  Received: {} bytes", summary.total_bytes_received);
+        out.push('\n');
+
+        if summary.scenarios.len() > 1 {
+            let mut names: Vec<_> = summary.scenarios.keys().collect();
+            names.sort();
+            let width = names.iter().map(|n| n.len()).max().unwrap_or(0).max("Scenario".len());
+
+            let _ = writeln!(out, "Scenarios:");
+            let _ = writeln!(
+                out,
+                "  {:<width$}  {:>10}  {:>10}  {:>10}  {:>12}",
+                "Scenario", "Requests", "Success", "Failed", "Avg (ms)"
+            );
+            for name in names {
+                let s = &summary.scenarios[name];
+                let _ = writeln!(
+                    out,
+                    "  {:<width$}  {:>10}  {:>10}  {:>10}  {:>12.2}",
+                    name,
+                    s.total_requests,
+                    s.successful_requests,
+                    s.failed_requests,
+                    s.avg_latency_ms
+                );
+            }
+            out.push('\n');
+        }
+
+        if !summary.steps.is_empty() {
+            let _ = writeln!(out, "Steps, response time (ms):");
+            out.push_str(&step_table(&summary.steps, |s| &s.response_time));
+            out.push('\n');
+            let _ = writeln!(out, "Steps, service time (ms):");
+            out.push_str(&step_table(&summary.steps, |s| &s.service_time));
+            out.push('\n');
+        }
 
         if !summary.errors_by_type.is_empty() {
-            println!("Errors:");
+            let _ = writeln!(out, "Errors:");
             for (error_type, count) in &summary.errors_by_type {
-                println!("  {error_type}: {count}");
+                let _ = writeln!(out, "  {error_type}: {count}");
             }
-            println!();
+            out.push('\n');
         }
 
-        println!("{}", "=".repeat(60));
+        if !summary.thresholds.is_empty() {
+            let _ = writeln!(out, "Thresholds:");
+            out.push_str(&threshold_table(&summary.thresholds));
+            out.push('\n');
+        }
 
-        Ok(())
+        let _ = writeln!(out, "{}", "=".repeat(60));
+
+        out
     }
 }
 
@@ -56,3 +121,153 @@ impl Default for ConsoleReporter {
         Self::new()
     }
 }
+
+/// Per-step breakdown as an aligned table, one line per step, showing the
+/// latency distribution picked by `latency`
+fn step_table(steps: &[StepSummary], latency: fn(&StepSummary) -> &LatencyDistribution) -> String {
+    let names = step_names(steps);
+    let width = names.iter().map(String::len).max().unwrap_or(0).max("Step".len());
+
+    let mut table =
+        format!("  {:<width$}  {:>8}  {:>7}  {:>8}", "Step", "Requests", "Errors", "RPS");
+    for column in LATENCY_COLUMNS {
+        let _ = write!(table, "  {column:>8}");
+    }
+    table.push('\n');
+
+    for (name, s) in names.iter().zip(steps) {
+        let _ = write!(
+            table,
+            "  {:<width$}  {:>8}  {:>7}  {:>8.2}",
+            name, s.total_requests, s.failed_requests, s.requests_per_sec
+        );
+        for value in latency_values(latency(s)) {
+            let _ = write!(table, "  {value:>8.2}");
+        }
+        table.push('\n');
+    }
+    table
+}
+
+/// Pass/fail line for every threshold with the observed value
+fn threshold_table(outcomes: &[ThresholdOutcome]) -> String {
+    let width = outcomes.iter().map(|o| o.expression.len()).max().unwrap_or(0);
+    let mut table = String::new();
+    for outcome in outcomes {
+        let status = if outcome.passed { "PASS" } else { "FAIL" };
+        let actual = actual_value(outcome);
+        let aborted = if outcome.aborted { " (aborted the run)" } else { "" };
+        let _ = writeln!(
+            table,
+            "  {status}  {:<width$}  actual: {actual}{aborted}",
+            outcome.expression
+        );
+    }
+    table
+}
+
+/// Display name of every step, prefixed with its scenario when the steps
+/// span more than one
+pub(crate) fn step_names(steps: &[StepSummary]) -> Vec<String> {
+    let multiple_scenarios = steps.iter().any(|s| s.scenario != steps[0].scenario);
+    steps
+        .iter()
+        .map(
+            |s| {
+                if multiple_scenarios {
+                    format!("{}/{}", s.scenario, s.step)
+                } else {
+                    s.step.clone()
+                }
+            },
+        )
+        .collect()
+}
+
+/// Observed value of a threshold with its unit
+pub(crate) fn actual_value(outcome: &ThresholdOutcome) -> String {
+    outcome.actual.map_or_else(
+        || "no data".to_string(),
+        |value| match outcome.unit.as_str() {
+            "" => format!("{value:.0}"),
+            "ms" | "%" => format!("{value:.2}{}", outcome.unit),
+            unit => format!("{value:.2} {unit}"),
+        },
+    )
+}
+
+/// Values of a distribution in [`LATENCY_COLUMNS`] order
+pub(crate) const fn latency_values(d: &LatencyDistribution) -> [f64; 9] {
+    [d.avg_ms, d.min_ms, d.p50_ms, d.p75_ms, d.p90_ms, d.p95_ms, d.p99_ms, d.p999_ms, d.max_ms]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(scenario: &str, step: &str, requests: u64) -> StepSummary {
+        StepSummary {
+            scenario: scenario.to_string(),
+            step: step.to_string(),
+            total_requests: requests,
+            successful_requests: requests,
+            failed_requests: 0,
+            requests_per_sec: 12.5,
+            service_time: LatencyDistribution {
+                avg_ms: 1.25,
+                min_ms: 0.5,
+                max_ms: 120.0,
+                p50_ms: 1.0,
+                p75_ms: 1.5,
+                p90_ms: 2.0,
+                p95_ms: 3.0,
+                p99_ms: 10.0,
+                p999_ms: 100.0,
+            },
+            response_time: LatencyDistribution { max_ms: 2500.0, ..LatencyDistribution::default() },
+        }
+    }
+
+    #[test]
+    fn test_step_table_is_aligned() {
+        let steps = [step("shop", "GET /", 10), step("shop", "POST /checkout", 5)];
+        let table = step_table(&steps, |s| &s.service_time);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("p99.9"));
+        assert!(lines[2].starts_with("  POST /checkout"));
+        assert!(lines.iter().all(|line| line.len() == lines[0].len()), "{table}");
+        assert!(lines[1].ends_with("  120.00"));
+        assert!(step_table(&steps, |s| &s.response_time).contains("2500.00"));
+    }
+
+    #[test]
+    fn test_threshold_table() {
+        let outcome = |expression: &str, passed, actual, unit: &str| ThresholdOutcome {
+            expression: expression.to_string(),
+            passed,
+            actual,
+            unit: unit.to_string(),
+            aborted: false,
+        };
+        let table = threshold_table(&[
+            outcome("http_req_duration.p95 < 500ms", true, Some(123.456), "ms"),
+            outcome("rps > 200", false, Some(150.0), "req/s"),
+            outcome("\"Login\".p99 < 1s", false, None, "ms"),
+            ThresholdOutcome { aborted: true, ..outcome("error_rate < 5%", false, Some(7.5), "%") },
+        ]);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines[0], "  PASS  http_req_duration.p95 < 500ms  actual: 123.46ms");
+        assert_eq!(lines[1], "  FAIL  rps > 200                      actual: 150.00 req/s");
+        assert!(lines[2].ends_with("actual: no data"));
+        assert!(lines[3].ends_with("actual: 7.50% (aborted the run)"));
+    }
+
+    #[test]
+    fn test_step_table_prefixes_scenarios() {
+        let steps = [step("browse", "GET /", 10), step("buy", "GET /", 5)];
+        let table = step_table(&steps, |s| &s.response_time);
+        assert!(table.contains("browse/GET /"));
+        assert!(table.contains("buy/GET /"));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::console::latency_values;
+use crate::error::{ReportError, Result};
+use crate::json::RunReport;
+
+/// Column-name suffixes of the latency statistics, in
+/// [`LATENCY_COLUMNS`](crate::console::LATENCY_COLUMNS) order
+const STAT_KEYS: [&str; 9] = ["avg", "min", "p50", "p75", "p90", "p95", "p99", "p999", "max"];
+
+/// Renders the per-step statistics of a [`RunReport`] as CSV, one row per
+/// step, for spreadsheets and plotting tools
+pub struct CsvReporter;
+
+impl CsvReporter {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&self, report: &RunReport) -> Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+
+        let mut header: Vec<String> =
+            ["scenario", "step", "requests", "successful", "failed", "rps"]
+                .map(String::from)
+                .to_vec();
+        for kind in ["response", "service"] {
+            header.extend(STAT_KEYS.iter().map(|stat| format!("{kind}_{stat}_ms")));
+        }
+        writer.write_record(&header)?;
+
+        for step in &report.summary.steps {
+            let mut record = vec![
+                step.scenario.clone(),
+                step.step.clone(),
+                step.total_requests.to_string(),
+                step.successful_requests.to_string(),
+                step.failed_requests.to_string(),
+                format!("{:.3}", step.requests_per_sec),
+            ];
+            for latency in [&step.response_time, &step.service_time] {
+                record.extend(latency_values(latency).iter().map(|value| format!("{value:.3}")));
+            }
+            writer.write_record(&record)?;
+        }
+
+        let bytes =
+            writer.into_inner().map_err(|e| ReportError::GenerationFailed(e.to_string()))?;
+        String::from_utf8(bytes).map_err(|e| ReportError::GenerationFailed(e.to_string()))
+    }
+}
+
+impl Default for CsvReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::json::RunMetadata;
+    use chrono::Utc;
+    use std::time::Duration;
+    use taran_metrics::HdrCollector;
+
+    #[test]
+    fn test_csv_rows() {
+        let collector = HdrCollector::new().unwrap();
+        collector.record_success("shop", "Browse, list", Duration::from_millis(10), 0, 0);
+        collector.record_failure("shop", "Login", "HTTP 500", Duration::from_millis(20));
+        let run = RunMetadata {
+            scenario: "Smoke".to_string(),
+            description: None,
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+        };
+        let report = RunReport::new(run, &(), collector.summary()).unwrap();
+
+        let csv = CsvReporter::new().render(&report).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(
+            lines[0].starts_with("scenario,step,requests,successful,failed,rps,response_avg_ms")
+        );
+        assert!(lines[0].ends_with("service_p999_ms,service_max_ms"));
+        assert!(lines[1].starts_with("shop,\"Browse, list\",1,1,0,"));
+        assert!(lines[2].starts_with("shop,Login,1,0,1,"));
+        assert_eq!(lines[2].split(',').count(), 6 + 2 * STAT_KEYS.len());
+    }
+}
@@ -10,6 +10,17 @@ pub enum ReportError {
 
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
+
+    #[error("CSV error: {0}")]
+    CsvError(#[from] csv::Error),
+
+    #[error("Not a taran result file: missing schema_version")]
+    MissingSchemaVersion,
+
+    #[error(
+        "Unsupported schema_version {found}; this version of taran reads schema_version {supported}"
+    )]
+    UnsupportedSchemaVersion { found: u64, supported: u32 },
 }
 
 pub type Result<T> = std::result::Result<T, ReportError>;
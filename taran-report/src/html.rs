@@ -0,0 +1,642 @@
+use crate::console::{actual_value, latency_values, step_names, LATENCY_COLUMNS};
+use crate::error::Result;
+use crate::json::RunReport;
+use std::fmt::Write;
+use taran_metrics::{HistogramData, LatencyDistribution, MetricsSummary, StepSummary};
+
+const STYLE: &str = r"
+body { font: 14px/1.5 system-ui, sans-serif; color: #1f2937; margin: 0; background: #f3f4f6; }
+main { max-width: 1100px; margin: 0 auto; padding: 24px; }
+h1 { margin: 0 0 4px; }
+h2 { margin: 32px 0 12px; font-size: 18px; }
+section { background: #fff; border-radius: 8px; padding: 16px 20px; margin-bottom: 16px; }
+.muted { color: #6b7280; }
+.meta { display: grid; grid-template-columns: max-content 1fr; gap: 2px 16px; margin-top: 12px; }
+.cards { display: grid; grid-template-columns: repeat(auto-fit, minmax(150px, 1fr)); gap: 12px; }
+.card { background: #fff; border-radius: 8px; padding: 12px 16px; }
+.card b { display: block; font-size: 22px; }
+.status { display: inline-block; padding: 2px 10px; border-radius: 12px; font-weight: 600; }
+.pass { background: #dcfce7; color: #166534; }
+.fail { background: #fee2e2; color: #991b1b; }
+table { border-collapse: collapse; width: 100%; }
+th, td { padding: 4px 8px; text-align: right; border-bottom: 1px solid #e5e7eb; white-space: nowrap; }
+th:first-child, td:first-child { text-align: left; }
+th { background: #f9fafb; cursor: pointer; user-select: none; }
+.scroll { overflow-x: auto; }
+svg { width: 100%; height: auto; }
+svg text { font-size: 11px; fill: #6b7280; }
+.legend span { margin-right: 16px; }
+.legend i { display: inline-block; width: 12px; height: 3px; margin-right: 4px; vertical-align: middle; }
+pre { background: #f9fafb; padding: 12px; overflow-x: auto; }
+";
+
+/// Sorts a table by the clicked column; numbers numerically, text otherwise
+const SCRIPT: &str = r"
+document.querySelectorAll('table.sortable th').forEach((th, column) => {
+  th.addEventListener('click', () => {
+    const body = th.closest('table').tBodies[0];
+    const ascending = th.dataset.order !== 'asc';
+    th.dataset.order = ascending ? 'asc' : 'desc';
+    const key = row => {
+      const text = row.cells[column].textContent;
+      const number = parseFloat(text);
+      return isNaN(number) ? text : number;
+    };
+    [...body.rows]
+      .sort((a, b) => (key(a) > key(b) ? 1 : key(a) < key(b) ? -1 : 0) * (ascending ? 1 : -1))
+      .forEach(row => body.appendChild(row));
+  });
+});
+";
+
+/// Colours of chart series, in order
+const PALETTE: [&str; 4] = ["#2563eb", "#dc2626", "#16a34a", "#9333ea"];
+
+const CHART_WIDTH: f64 = 760.0;
+const CHART_HEIGHT: f64 = 240.0;
+const MARGIN_LEFT: f64 = 56.0;
+const MARGIN_RIGHT: f64 = 16.0;
+const MARGIN_TOP: f64 = 12.0;
+const MARGIN_BOTTOM: f64 = 32.0;
+
+/// Charts with more points than this get no per-point tooltips
+const MAX_TOOLTIP_POINTS: usize = 300;
+
+/// Number of bars in the latency histogram
+const HISTOGRAM_BINS: usize = 30;
+
+/// Deepest percentile plotted, in nines (5 = 99.999%)
+const MAX_NINES: f64 = 5.0;
+
+/// Renders a [`RunReport`] as one self-contained HTML page.
+///
+/// Styles, the table-sorting script and every chart (inline SVG) are
+/// embedded, so the file opens offline and can be attached anywhere.
+pub struct HtmlReporter;
+
+/// One line of a chart
+struct Series<'a> {
+    name: &'a str,
+    points: Vec<(f64, f64)>,
+}
+
+/// Labelled positions along an axis
+type Ticks = Vec<(f64, String)>;
+
+impl HtmlReporter {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Render the whole report page
+    pub fn render(&self, report: &RunReport) -> Result<String> {
+        let summary = &report.summary;
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <title>{} — Taran report</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<main>\n",
+            escape(&report.run.scenario)
+        );
+
+        html.push_str(&header(report));
+        html.push_str(&overview(summary));
+        html.push_str(&timeline_charts(summary));
+        html.push_str(&step_tables(&summary.steps));
+        html.push_str(&distribution_charts(summary));
+        html.push_str(&errors(summary));
+        html.push_str(&thresholds(summary));
+
+        let config = serde_json::to_string_pretty(&report.config)?;
+        let _ = write!(
+            html,
+            "<h2>Configuration</h2>\n<section><details><summary>Effective scenario \
+             configuration</summary><pre>{}</pre></details></section>\n",
+            escape(&config)
+        );
+
+        let _ = write!(html, "</main>\n<script>{SCRIPT}</script>\n</body>\n</html>\n");
+        Ok(html)
+    }
+}
+
+impl Default for HtmlReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Scenario name, description and run metadata
+fn header(report: &RunReport) -> String {
+    let run = &report.run;
+    let summary = &report.summary;
+    let status = if summary.aborted_by().is_some() {
+        "<span class=\"status fail\">ABORTED</span>"
+    } else if summary.passed() {
+        "<span class=\"status pass\">PASSED</span>"
+    } else {
+        "<span class=\"status fail\">FAILED</span>"
+    };
+
+    let mut html = format!("<section>\n<h1>{} {status}</h1>\n", escape(&run.scenario));
+    if let Some(description) = &run.description {
+        let _ = writeln!(html, "<div class=\"muted\">{}</div>", escape(description));
+    }
+    html.push_str("<div class=\"meta\">\n");
+    for (label, value) in [
+        ("Started", run.started_at.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+        ("Finished", run.finished_at.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+        ("Duration", format!("{:.1} s", summary.duration_secs)),
+        ("Taran", format!("{} (schema {})", report.taran_version, report.schema_version)),
+    ] {
+        let _ =
+            writeln!(html, "<span class=\"muted\">{label}</span><span>{}</span>", escape(&value));
+    }
+    html.push_str("</div>\n</section>\n");
+    html
+}
+
+/// Headline numbers as cards
+fn overview(summary: &MetricsSummary) -> String {
+    let error_rate = rate(summary.failed_requests, summary.total_requests as f64) * 100.0;
+    let cards = [
+        ("Requests", summary.total_requests.to_string()),
+        ("Failed", summary.failed_requests.to_string()),
+        ("Error rate", format!("{error_rate:.2}%")),
+        ("Throughput", format!("{:.2} req/s", summary.requests_per_sec)),
+        ("p95 response", format!("{:.2} ms", summary.response_time.p95_ms)),
+        ("p99 response", format!("{:.2} ms", summary.response_time.p99_ms)),
+    ];
+    let mut html = String::from("<div class=\"cards\">\n");
+    for (label, value) in cards {
+        let _ = writeln!(
+            html,
+            "<div class=\"card\"><span class=\"muted\">{label}</span><b>{value}</b></div>"
+        );
+    }
+    html.push_str("</div>\n");
+    html
+}
+
+/// Throughput, active VUs and latency over the time series
+fn timeline_charts(summary: &MetricsSummary) -> String {
+    let timeline = &summary.timeline;
+    if timeline.is_empty() {
+        return String::new();
+    }
+    let over_time = |value: fn(&taran_metrics::IntervalSnapshot) -> f64| {
+        timeline.iter().map(|window| (window.elapsed_secs, value(window))).collect()
+    };
+
+    let throughput = [
+        Series { name: "Requests/s", points: over_time(|w| w.requests_per_sec) },
+        Series {
+            name: "Errors/s",
+            points: over_time(|w| rate(w.failed_requests, w.duration_secs)),
+        },
+        Series { name: "Active VUs", points: over_time(|w| w.active_vus as f64) },
+    ];
+    let latency = [
+        Series { name: "p50", points: over_time(|w| w.response_time.p50_ms) },
+        Series { name: "p95", points: over_time(|w| w.response_time.p95_ms) },
+        Series { name: "p99", points: over_time(|w| w.response_time.p99_ms) },
+        Series { name: "max", points: over_time(|w| w.response_time.max_ms) },
+    ];
+
+    let mut html = String::from("<h2>Throughput over time</h2>\n<section>\n");
+    html.push_str(&line_chart(&throughput, "elapsed (s)", None));
+    html.push_str("</section>\n<h2>Response time over time (ms)</h2>\n<section>\n");
+    html.push_str(&line_chart(&latency, "elapsed (s)", None));
+    html.push_str("</section>\n");
+    html
+}
+
+/// Sortable per-step tables for response and service time
+fn step_tables(steps: &[StepSummary]) -> String {
+    if steps.is_empty() {
+        return String::new();
+    }
+    let mut html = String::new();
+    for (title, latency) in [
+        (
+            "Steps, response time (ms)",
+            (|s| &s.response_time) as fn(&StepSummary) -> &LatencyDistribution,
+        ),
+        ("Steps, service time (ms)", |s| &s.service_time),
+    ] {
+        let _ = write!(
+            html,
+            "<h2>{title}</h2>\n<section class=\"scroll\">\n<table class=\"sortable\">\n<thead><tr>\
+             <th>Step</th><th>Requests</th><th>Errors</th><th>RPS</th>"
+        );
+        for column in LATENCY_COLUMNS {
+            let _ = write!(html, "<th>{column}</th>");
+        }
+        html.push_str("</tr></thead>\n<tbody>\n");
+        for (name, step) in step_names(steps).iter().zip(steps) {
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td>",
+                escape(name),
+                step.total_requests,
+                step.failed_requests,
+                step.requests_per_sec
+            );
+            for value in latency_values(latency(step)) {
+                let _ = write!(html, "<td>{value:.2}</td>");
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</tbody>\n</table>\n</section>\n");
+    }
+    html
+}
+
+/// Latency histogram and percentile distribution over every step
+fn distribution_charts(summary: &MetricsSummary) -> String {
+    let response = merge_counts(summary.histograms.iter().map(|h| &h.response));
+    let service = merge_counts(summary.histograms.iter().map(|h| &h.service));
+    if response.is_empty() {
+        return String::new();
+    }
+
+    let mut html = String::from("<h2>Response time histogram</h2>\n<section>\n");
+    html.push_str(&bar_chart(&histogram_bins(&response)));
+    html.push_str("</section>\n<h2>Latency by percentile (ms)</h2>\n<section>\n");
+    let percentiles = [
+        Series { name: "Response time", points: percentile_points(&response) },
+        Series { name: "Service time", points: percentile_points(&service) },
+    ];
+    let ticks =
+        (0..=MAX_NINES as u32).map(|nines| (f64::from(nines), nines_label(nines))).collect();
+    html.push_str(&line_chart(&percentiles, "percentile", Some(ticks)));
+    html.push_str("</section>\n");
+    html
+}
+
+/// Error types sorted by count
+fn errors(summary: &MetricsSummary) -> String {
+    if summary.errors_by_type.is_empty() {
+        return String::new();
+    }
+    let mut errors: Vec<_> = summary.errors_by_type.iter().collect();
+    errors.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut html = String::from(
+        "<h2>Errors</h2>\n<section>\n<table class=\"sortable\">\n<thead><tr><th>Error</th>\
+         <th>Count</th><th>% of requests</th></tr></thead>\n<tbody>\n",
+    );
+    for (error, &count) in errors {
+        let share = rate(count, summary.total_requests as f64) * 100.0;
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{count}</td><td>{share:.2}%</td></tr>",
+            escape(error)
+        );
+    }
+    html.push_str("</tbody>\n</table>\n</section>\n");
+    html
+}
+
+/// Pass/fail of every threshold
+fn thresholds(summary: &MetricsSummary) -> String {
+    if summary.thresholds.is_empty() {
+        return String::new();
+    }
+    let mut html = String::from(
+        "<h2>Thresholds</h2>\n<section>\n<table>\n<thead><tr><th>Rule</th><th>Actual</th>\
+         <th>Result</th></tr></thead>\n<tbody>\n",
+    );
+    for outcome in &summary.thresholds {
+        let status = match (outcome.passed, outcome.aborted) {
+            (true, _) => "<span class=\"status pass\">PASS</span>",
+            (false, false) => "<span class=\"status fail\">FAIL</span>",
+            (false, true) => "<span class=\"status fail\">FAIL, aborted the run</span>",
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{status}</td></tr>",
+            escape(&outcome.expression),
+            escape(&actual_value(outcome))
+        );
+    }
+    html.push_str("</tbody>\n</table>\n</section>\n");
+    html
+}
+
+/// SVG line chart with a legend; the y axis starts at zero
+fn line_chart(series: &[Series<'_>], x_label: &str, x_ticks: Option<Ticks>) -> String {
+    let points = series.iter().flat_map(|s| &s.points);
+    let x_min = points.clone().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let x_max = points.clone().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    let y_max = nice_ceiling(points.map(|p| p.1).fold(0.0, f64::max));
+    let (x_min, x_max) = if x_min < x_max { (x_min, x_max) } else { (x_min - 1.0, x_min + 1.0) };
+    let x_ticks = x_ticks.unwrap_or_else(|| {
+        (0..=5)
+            .map(|i| {
+                let x = x_min + (x_max - x_min) * f64::from(i) / 5.0;
+                (x, format_number(x))
+            })
+            .collect()
+    });
+
+    let plot = Plot { x_min, x_max, y_max };
+    let mut svg = plot.frame(x_label, &x_ticks);
+    let tooltips = series.iter().map(|s| s.points.len()).sum::<usize>() <= MAX_TOOLTIP_POINTS;
+    for (s, color) in series.iter().zip(PALETTE.iter().cycle()) {
+        let path: Vec<String> =
+            s.points.iter().map(|&(x, y)| format!("{:.1},{:.1}", plot.x(x), plot.y(y))).collect();
+        let _ = writeln!(
+            svg,
+            "<polyline fill=\"none\" stroke=\"{color}\" stroke-width=\"2\" points=\"{}\"/>",
+            path.join(" ")
+        );
+        if tooltips {
+            for &(x, y) in &s.points {
+                let _ = writeln!(
+                    svg,
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{color}\"><title>{}: {} at \
+                     {}</title></circle>",
+                    plot.x(x),
+                    plot.y(y),
+                    escape(s.name),
+                    format_number(y),
+                    format_number(x)
+                );
+            }
+        }
+    }
+    svg.push_str("</svg>\n<div class=\"legend\">");
+    for (s, color) in series.iter().zip(PALETTE.iter().cycle()) {
+        let _ = write!(svg, "<span><i style=\"background:{color}\"></i>{}</span>", escape(s.name));
+    }
+    svg.push_str("</div>\n");
+    svg
+}
+
+/// SVG bar chart of `(label, count)` bars
+fn bar_chart(bars: &[(String, u64)]) -> String {
+    let y_max = nice_ceiling(bars.iter().map(|bar| bar.1 as f64).fold(0.0, f64::max));
+    let plot = Plot { x_min: 0.0, x_max: bars.len() as f64, y_max };
+    let ticks: Ticks = bars
+        .iter()
+        .enumerate()
+        .step_by(bars.len().div_ceil(6).max(1))
+        .map(|(i, (label, _))| (i as f64 + 0.5, label.clone()))
+        .collect();
+
+    let mut svg = plot.frame("response time (ms)", &ticks);
+    let width = plot.x(1.0) - plot.x(0.0);
+    for (i, (label, count)) in bars.iter().enumerate() {
+        let top = plot.y(*count as f64);
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{top:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\">\
+             <title>{label} ms: {count} requests</title></rect>",
+            plot.x(i as f64) + 1.0,
+            (width - 2.0).max(1.0),
+            plot.y(0.0) - top,
+            PALETTE[0]
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Maps data coordinates onto the chart area
+struct Plot {
+    x_min: f64,
+    x_max: f64,
+    y_max: f64,
+}
+
+impl Plot {
+    fn x(&self, value: f64) -> f64 {
+        let width = CHART_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+        ((value - self.x_min) / (self.x_max - self.x_min)).mul_add(width, MARGIN_LEFT)
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        let height = CHART_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+        (1.0 - value / self.y_max).mul_add(height, MARGIN_TOP)
+    }
+
+    /// Opening `<svg>` tag with grid lines and axis labels
+    fn frame(&self, x_label: &str, x_ticks: &[(f64, String)]) -> String {
+        let mut svg = format!("<svg viewBox=\"0 0 {CHART_WIDTH} {CHART_HEIGHT}\">\n");
+        for i in 0..=4 {
+            let value = self.y_max * f64::from(i) / 4.0;
+            let y = self.y(value);
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{MARGIN_LEFT}\" x2=\"{:.1}\" y1=\"{y:.1}\" y2=\"{y:.1}\" \
+                 stroke=\"#e5e7eb\"/><text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+                CHART_WIDTH - MARGIN_RIGHT,
+                MARGIN_LEFT - 6.0,
+                y + 4.0,
+                format_number(value)
+            );
+        }
+        let baseline = self.y(0.0);
+        for (x, label) in x_ticks {
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+                self.x(*x),
+                baseline + 16.0,
+                escape(label)
+            );
+        }
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            CHART_WIDTH - MARGIN_RIGHT,
+            CHART_HEIGHT - 2.0,
+            escape(x_label)
+        );
+        svg
+    }
+}
+
+/// Recorded `(microseconds, count)` pairs of several histograms, merged
+/// and sorted by value
+fn merge_counts<'a>(histograms: impl Iterator<Item = &'a HistogramData>) -> Vec<(u64, u64)> {
+    HistogramData::merge(histograms).counts.into_iter().map(<(u64, u64)>::from).collect()
+}
+
+/// Counts in [`HISTOGRAM_BINS`] bins of equal width on a log scale, so
+/// both the fast bulk and a slow tail stay visible
+fn histogram_bins(counts: &[(u64, u64)]) -> Vec<(String, u64)> {
+    let (Some(&(min, _)), Some(&(max, _))) = (counts.first(), counts.last()) else {
+        return Vec::new();
+    };
+    let (min, max) = (min.max(1) as f64, max.max(1) as f64);
+    if max <= min {
+        return vec![(format_number(min / 1000.0), counts.iter().map(|c| c.1).sum())];
+    }
+    let ratio = (max / min).ln();
+    let edge = |bin: usize| min * (ratio * bin as f64 / HISTOGRAM_BINS as f64).exp();
+
+    let mut bins: Vec<(String, u64)> =
+        (0..HISTOGRAM_BINS).map(|bin| (format_number(edge(bin) / 1000.0), 0)).collect();
+    for &(value, count) in counts {
+        let position = (value.max(1) as f64 / min).ln() / ratio * HISTOGRAM_BINS as f64;
+        let bin = (position as usize).min(HISTOGRAM_BINS - 1);
+        bins[bin].1 += count;
+    }
+    bins
+}
+
+/// Latency in milliseconds at percentiles spaced evenly in nines, as
+/// `(nines, latency)`; stops where the sample count runs out of resolution
+fn percentile_points(counts: &[(u64, u64)]) -> Vec<(f64, f64)> {
+    let total: u64 = counts.iter().map(|c| c.1).sum();
+    if total == 0 {
+        return Vec::new();
+    }
+    let deepest = (total as f64).log10().min(MAX_NINES);
+    let steps = (deepest * 10.0).ceil() as u32;
+    (0..=steps)
+        .map(|step| {
+            let nines = (f64::from(step) / 10.0).min(deepest);
+            let quantile = 1.0 - 10f64.powf(-nines);
+            (nines, value_at_quantile(counts, total, quantile) as f64 / 1000.0)
+        })
+        .collect()
+}
+
+fn value_at_quantile(counts: &[(u64, u64)], total: u64, quantile: f64) -> u64 {
+    let rank = ((quantile * total as f64).ceil() as u64).max(1);
+    let mut seen = 0;
+    for &(value, count) in counts {
+        seen += count;
+        if seen >= rank {
+            return value;
+        }
+    }
+    counts.last().map_or(0, |c| c.0)
+}
+
+/// "0%", "90%", "99%", "99.9%", …
+fn nines_label(nines: u32) -> String {
+    match nines {
+        0 => "0%".to_string(),
+        1 => "90%".to_string(),
+        n => format!("99.{}%", "9".repeat(n as usize - 2)).replace(".%", "%"),
+    }
+}
+
+/// Smallest 1, 2 or 5 × 10ⁿ at or above `value`, so axes end on round numbers
+fn nice_ceiling(value: f64) -> f64 {
+    if value <= 0.0 || !value.is_finite() {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(value.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|step| step * magnitude)
+        .find(|&ceiling| ceiling >= value)
+        .unwrap_or(10.0 * magnitude)
+}
+
+fn format_number(value: f64) -> String {
+    if value.abs() >= 100.0 || value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else if value.abs() >= 1.0 {
+        format!("{value:.1}")
+    } else {
+        format!("{value:.2}")
+    }
+}
+
+/// `count` per unit of `over`, or zero when there is nothing to divide by
+fn rate(count: u64, over: f64) -> f64 {
+    if over > 0.0 {
+        count as f64 / over
+    } else {
+        0.0
+    }
+}
+
+/// Escape text for use in HTML content and attribute values
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::json::RunMetadata;
+    use chrono::Utc;
+    use std::time::Duration;
+    use taran_metrics::HdrCollector;
+
+    fn report() -> RunReport {
+        let collector = HdrCollector::new().unwrap();
+        for ms in 1..=100 {
+            collector.record_success("default", "Home", Duration::from_millis(ms), 0, 0);
+        }
+        collector.record_interval();
+        collector.record_failure("default", "<Login>", "HTTP 500", Duration::from_millis(900));
+        collector.record_interval();
+
+        let run = RunMetadata {
+            scenario: "Checkout & pay".to_string(),
+            description: Some("Nightly".to_string()),
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+        };
+        RunReport::new(run, &serde_json::json!({ "scenario": {} }), collector.summary()).unwrap()
+    }
+
+    #[test]
+    fn test_render_is_self_contained() {
+        let html = HtmlReporter::new().render(&report()).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("http://") && !html.contains("https://"));
+        assert!(!html.contains("<script src") && !html.contains("<link"));
+        assert!(html.contains("Checkout &amp; pay"));
+        assert!(html.contains("<td>&lt;Login&gt;</td>"));
+        assert!(html.contains("<td>HTTP 500</td><td>1</td>"));
+        assert!(html.contains("Throughput over time"));
+        assert!(html.contains("Latency by percentile"));
+        assert!(html.contains("status fail\">FAILED"));
+    }
+
+    #[test]
+    fn test_histogram_bins_and_percentiles() {
+        let counts: Vec<(u64, u64)> = (1..=1000).map(|ms| (ms * 1000, 1)).collect();
+        let bins = histogram_bins(&counts);
+        assert_eq!(bins.len(), HISTOGRAM_BINS);
+        assert_eq!(bins.iter().map(|b| b.1).sum::<u64>(), 1000);
+        assert_eq!(bins[0].0, "1");
+
+        let points = percentile_points(&counts);
+        assert_eq!(points.first().copied(), Some((0.0, 1.0)));
+        assert_eq!(points.last().copied(), Some((3.0, 999.0)));
+        assert!((value_at_quantile(&counts, 1000, 0.99) as f64 / 1000.0 - 990.0).abs() < 1e-9);
+        assert_eq!(histogram_bins(&[(5000, 3)]), [("5".to_string(), 3)]);
+    }
+
+    #[test]
+    fn test_axis_helpers() {
+        assert!((nice_ceiling(0.0) - 1.0).abs() < 1e-9);
+        assert!((nice_ceiling(7.3) - 10.0).abs() < 1e-9);
+        assert!((nice_ceiling(130.0) - 200.0).abs() < 1e-9);
+        assert_eq!(nines_label(2), "99%");
+        assert_eq!(nines_label(4), "99.99%");
+    }
+}
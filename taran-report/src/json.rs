@@ -0,0 +1,139 @@
+use crate::error::{ReportError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use taran_metrics::MetricsSummary;
+
+/// Version of the exported document layout. Bumped whenever a field is
+/// renamed, removed or changes meaning; adding fields keeps the version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Everything known about a finished run, as exported to JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    pub schema_version: u32,
+    /// Version of taran that produced the document
+    pub taran_version: String,
+    pub run: RunMetadata,
+    /// Scenario configuration the run used, after command-line overrides
+    pub config: serde_json::Value,
+    /// Totals, per-step stats, time series, threshold outcomes and histograms
+    pub summary: MetricsSummary,
+}
+
+/// When and what was run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub scenario: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+impl RunReport {
+    pub fn new(run: RunMetadata, config: &impl Serialize, summary: MetricsSummary) -> Result<Self> {
+        Ok(Self {
+            schema_version: SCHEMA_VERSION,
+            taran_version: env!("CARGO_PKG_VERSION").to_string(),
+            run,
+            config: serde_json::to_value(config)?,
+            summary,
+        })
+    }
+
+    /// Load a report written by [`JsonReporter`], refusing documents of
+    /// any other schema version
+    pub fn load(path: &Path) -> Result<Self> {
+        let document: serde_json::Value =
+            serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        let found = document
+            .get("schema_version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or(ReportError::MissingSchemaVersion)?;
+        if found != u64::from(SCHEMA_VERSION) {
+            return Err(ReportError::UnsupportedSchemaVersion { found, supported: SCHEMA_VERSION });
+        }
+        Ok(serde_json::from_value(document)?)
+    }
+}
+
+/// Renders a [`RunReport`] as pretty-printed JSON
+pub struct JsonReporter;
+
+impl JsonReporter {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// The report as pretty-printed JSON
+    pub fn render(&self, report: &RunReport) -> Result<String> {
+        Ok(serde_json::to_string_pretty(report)? + "\n")
+    }
+}
+
+impl Default for JsonReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use taran_metrics::HdrCollector;
+
+    #[test]
+    fn test_json_export() {
+        let collector = HdrCollector::new().unwrap();
+        collector.record_success("default", "Home", Duration::from_millis(12), 100, 2048);
+        collector.record_failure("default", "Home", "HTTP 500", Duration::from_millis(30));
+        collector.record_interval();
+
+        let run = RunMetadata {
+            scenario: "Smoke".to_string(),
+            description: None,
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+        };
+        let config = serde_json::json!({ "scenario": { "name": "Smoke" } });
+        let report = RunReport::new(run, &config, collector.summary()).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.json");
+        std::fs::write(&path, JsonReporter::new().render(&report).unwrap()).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["config"]["scenario"]["name"], "Smoke");
+        assert_eq!(json["summary"]["steps"][0]["failed_requests"], 1);
+        assert_eq!(json["summary"]["timeline"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            json["summary"]["histograms"][0]["service"]["counts"].as_array().unwrap().len(),
+            2
+        );
+
+        let restored = RunReport::load(&path).unwrap();
+        assert_eq!(restored.run.started_at, report.run.started_at);
+        assert_eq!(restored.summary.histograms, report.summary.histograms);
+    }
+
+    #[test]
+    fn test_load_rejects_other_schema_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.json");
+
+        std::fs::write(&path, r#"{ "schema_version": 2 }"#).unwrap();
+        let err = RunReport::load(&path).unwrap_err();
+        assert!(matches!(err, ReportError::UnsupportedSchemaVersion { found: 2, supported: 1 }));
+        assert!(err.to_string().contains("schema_version 2"));
+
+        std::fs::write(&path, r#"{ "summary": {} }"#).unwrap();
+        assert!(matches!(RunReport::load(&path), Err(ReportError::MissingSchemaVersion)));
+    }
+}
@@ -0,0 +1,141 @@
+use crate::console::actual_value;
+use crate::html::escape;
+use crate::json::RunReport;
+use std::fmt::Write;
+
+/// Renders a [`RunReport`] as a `JUnit` XML test suite so CI servers can show
+/// the run as test results.
+///
+/// Every threshold is a test case. Without thresholds every step is one,
+/// failing when any of its requests failed — the same rule that decides
+/// the exit code of `taran run`.
+pub struct JunitReporter;
+
+/// One `<testcase>`: its name and the failure message, if it failed
+type TestCase = (String, Option<String>);
+
+impl JunitReporter {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&self, report: &RunReport) -> String {
+        let summary = &report.summary;
+        let cases: Vec<TestCase> = if summary.thresholds.is_empty() {
+            summary
+                .steps
+                .iter()
+                .map(|step| {
+                    let failure = (step.failed_requests > 0).then(|| {
+                        format!(
+                            "{} of {} requests failed",
+                            step.failed_requests, step.total_requests
+                        )
+                    });
+                    (format!("{}/{}", step.scenario, step.step), failure)
+                })
+                .collect()
+        } else {
+            summary
+                .thresholds
+                .iter()
+                .map(|outcome| {
+                    let failure = (!outcome.passed).then(|| {
+                        let aborted = if outcome.aborted { ", aborted the run" } else { "" };
+                        format!("actual: {}{aborted}", actual_value(outcome))
+                    });
+                    (outcome.expression.clone(), failure)
+                })
+                .collect()
+        };
+        let failures = cases.iter().filter(|case| case.1.is_some()).count();
+        let name = escape(&report.run.scenario);
+
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuites name=\"taran\" tests=\"{}\" failures=\"{failures}\" time=\"{:.3}\">",
+            cases.len(),
+            summary.duration_secs
+        );
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{name}\" tests=\"{}\" failures=\"{failures}\" errors=\"0\" \
+             time=\"{:.3}\" timestamp=\"{}\">",
+            cases.len(),
+            summary.duration_secs,
+            report.run.started_at.format("%Y-%m-%dT%H:%M:%S")
+        );
+        for (case, failure) in &cases {
+            let case = escape(case);
+            match failure {
+                None => {
+                    let _ = writeln!(xml, "    <testcase name=\"{case}\" classname=\"{name}\"/>");
+                }
+                Some(message) => {
+                    let message = escape(message);
+                    let _ = writeln!(
+                        xml,
+                        "    <testcase name=\"{case}\" classname=\"{name}\">\n      \
+                         <failure message=\"{message}\">{message}</failure>\n    </testcase>"
+                    );
+                }
+            }
+        }
+        xml.push_str("  </testsuite>\n</testsuites>\n");
+        xml
+    }
+}
+
+impl Default for JunitReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::json::RunMetadata;
+    use chrono::Utc;
+    use std::time::Duration;
+    use taran_metrics::{HdrCollector, ThresholdOutcome};
+
+    fn report() -> RunReport {
+        let collector = HdrCollector::new().unwrap();
+        collector.record_success("shop", "Browse", Duration::from_millis(10), 0, 0);
+        collector.record_failure("shop", "Login", "HTTP 500", Duration::from_millis(20));
+        let run = RunMetadata {
+            scenario: "Smoke & soak".to_string(),
+            description: None,
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+        };
+        RunReport::new(run, &(), collector.summary()).unwrap()
+    }
+
+    #[test]
+    fn test_steps_as_test_cases() {
+        let xml = JunitReporter::new().render(&report());
+        assert!(xml.contains("<testsuite name=\"Smoke &amp; soak\" tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("<testcase name=\"shop/Browse\" classname=\"Smoke &amp; soak\"/>"));
+        assert!(xml.contains("<failure message=\"1 of 1 requests failed\">"));
+    }
+
+    #[test]
+    fn test_thresholds_as_test_cases() {
+        let mut report = report();
+        report.summary.thresholds = vec![ThresholdOutcome {
+            expression: "http_req_duration.p95 < 5ms".to_string(),
+            passed: false,
+            actual: Some(20.0),
+            unit: "ms".to_string(),
+            aborted: false,
+        }];
+        let xml = JunitReporter::new().render(&report);
+        assert!(xml.contains("tests=\"1\" failures=\"1\""));
+        assert!(xml.contains("<testcase name=\"http_req_duration.p95 &lt; 5ms\""));
+        assert!(xml.contains("<failure message=\"actual: 20.00ms\">"));
+    }
+}
@@ -1,5 +1,17 @@
+pub mod compare;
 pub mod console;
+pub mod csv;
 pub mod error;
+pub mod html;
+pub mod json;
+pub mod junit;
+pub mod markdown;
 
+pub use compare::{MetricDelta, RunComparison, StepComparison, Tolerances};
 pub use console::ConsoleReporter;
+pub use csv::CsvReporter;
 pub use error::{ReportError, Result};
+pub use html::HtmlReporter;
+pub use json::{JsonReporter, RunMetadata, RunReport, SCHEMA_VERSION};
+pub use junit::JunitReporter;
+pub use markdown::MarkdownReporter;
@@ -0,0 +1,156 @@
+use crate::console::{actual_value, latency_values, step_names, LATENCY_COLUMNS};
+use crate::json::RunReport;
+use std::fmt::Write;
+use taran_metrics::MetricsSummary;
+
+/// Renders a [`RunReport`] as GitHub-flavoured Markdown, e.g. for a
+/// pull-request comment or a CI job summary
+pub struct MarkdownReporter;
+
+impl MarkdownReporter {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&self, report: &RunReport) -> String {
+        let summary = &report.summary;
+        let status = if summary.aborted_by().is_some() {
+            "❌ aborted"
+        } else if summary.passed() {
+            "✅ passed"
+        } else {
+            "❌ failed"
+        };
+
+        let mut md = format!("# {} — {status}\n\n", cell(&report.run.scenario));
+        if let Some(description) = &report.run.description {
+            let _ = writeln!(md, "{description}\n");
+        }
+        let _ = writeln!(
+            md,
+            "Started {}, ran {:.1} s, taran {}.\n",
+            report.run.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            summary.duration_secs,
+            report.taran_version
+        );
+
+        md.push_str(&totals(summary));
+
+        md.push_str("\n## Latency (ms)\n\n| | Service | Response |\n|---|---:|---:|\n");
+        let service = latency_values(&summary.service_time);
+        let response = latency_values(&summary.response_time);
+        for ((column, service), response) in LATENCY_COLUMNS.iter().zip(service).zip(response) {
+            let _ = writeln!(md, "| {column} | {service:.2} | {response:.2} |");
+        }
+
+        if !summary.steps.is_empty() {
+            md.push_str("\n## Steps, response time (ms)\n\n| Step | Requests | Errors | RPS |");
+            for column in LATENCY_COLUMNS {
+                let _ = write!(md, " {column} |");
+            }
+            md.push_str("\n|---|---:|---:|---:|");
+            md.push_str(&"---:|".repeat(LATENCY_COLUMNS.len()));
+            md.push('\n');
+            for (name, step) in step_names(&summary.steps).iter().zip(&summary.steps) {
+                let _ = write!(
+                    md,
+                    "| {} | {} | {} | {:.2} |",
+                    cell(name),
+                    step.total_requests,
+                    step.failed_requests,
+                    step.requests_per_sec
+                );
+                for value in latency_values(&step.response_time) {
+                    let _ = write!(md, " {value:.2} |");
+                }
+                md.push('\n');
+            }
+        }
+
+        if !summary.errors_by_type.is_empty() {
+            let mut errors: Vec<_> = summary.errors_by_type.iter().collect();
+            errors.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+            md.push_str("\n## Errors\n\n| Error | Count |\n|---|---:|\n");
+            for (error, count) in errors {
+                let _ = writeln!(md, "| {} | {count} |", cell(error));
+            }
+        }
+
+        if !summary.thresholds.is_empty() {
+            md.push_str("\n## Thresholds\n\n| | Rule | Actual |\n|---|---|---:|\n");
+            for outcome in &summary.thresholds {
+                let status = match (outcome.passed, outcome.aborted) {
+                    (true, _) => "✅",
+                    (false, false) => "❌",
+                    (false, true) => "❌ aborted the run",
+                };
+                let _ = writeln!(
+                    md,
+                    "| {status} | `{}` | {} |",
+                    outcome.expression.replace('|', "\\|"),
+                    cell(&actual_value(outcome))
+                );
+            }
+        }
+        md
+    }
+}
+
+impl Default for MarkdownReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn totals(summary: &MetricsSummary) -> String {
+    let mut md = String::from("| Requests | Failed | Success rate | Throughput |\n");
+    md.push_str("|---:|---:|---:|---:|\n");
+    let _ = writeln!(
+        md,
+        "| {} | {} | {:.2}% | {:.2} req/s |",
+        summary.total_requests,
+        summary.failed_requests,
+        summary.success_rate,
+        summary.requests_per_sec
+    );
+    md
+}
+
+/// Text safe to put in a table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::json::RunMetadata;
+    use chrono::Utc;
+    use std::time::Duration;
+    use taran_metrics::HdrCollector;
+
+    #[test]
+    fn test_markdown_tables() {
+        let collector = HdrCollector::new().unwrap();
+        collector.record_success("default", "Home", Duration::from_millis(10), 0, 0);
+        collector.record_failure("default", "A|B", "HTTP 500", Duration::from_millis(20));
+        let run = RunMetadata {
+            scenario: "Smoke".to_string(),
+            description: None,
+            started_at: Utc::now(),
+            finished_at: Utc::now(),
+        };
+        let report = RunReport::new(run, &(), collector.summary()).unwrap();
+
+        let md = MarkdownReporter::new().render(&report);
+        assert!(md.starts_with("# Smoke — ❌ failed\n"));
+        assert!(md.contains("| 2 | 1 | 50.00% |"));
+        assert!(md.contains("| A\\|B | 1 | 1 |"));
+        assert!(md.contains("| HTTP 500 | 1 |"));
+        // Every step row has as many cells as the header
+        let header = md.lines().find(|l| l.starts_with("| Step |")).unwrap();
+        let row = md.lines().find(|l| l.starts_with("| Home |")).unwrap();
+        assert_eq!(header.matches(" |").count(), row.matches(" |").count());
+    }
+}